@@ -1,5 +1,9 @@
 //====================================================================
 
+use feathered_shipyard::prelude::{PluginGroup, PluginGroupBuilder};
+
+//====================================================================
+
 pub use feathered_common as common;
 
 pub use feathered_physics as physics;
@@ -22,13 +26,16 @@ pub use feathered_tools as tools;
 //====================================================================
 
 pub struct DefaultPlugins;
-impl feathered_shipyard::prelude::Plugin for DefaultPlugins {
-    fn build_plugin(self, builder: &mut feathered_shipyard::prelude::WorkloadBuilder) {
-        builder
+impl PluginGroup for DefaultPlugins {
+    fn build(self) -> PluginGroupBuilder {
+        PluginGroupBuilder::start::<Self>()
             .add_plugin(feathered_common::CommonPlugin)
+            .add_plugin(feathered_render_tools::RenderComponentsPlugin)
+            .add_plugin(feathered_render_tools::RenderUtilsPlugin)
             .add_plugin(feathered_render_tools::FullRenderToolsPlugin)
             .add_plugin(feathered_spatial::SpatialPlugin)
-            .add_plugin(feathered_tools::input::InputPlugin);
+            .add_plugin(feathered_tools::input::KeyboardPlugin)
+            .add_plugin(feathered_tools::input::MousePlugin)
     }
 }
 
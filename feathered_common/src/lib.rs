@@ -10,7 +10,7 @@ use feathered_shipyard::{
     events::{Event, EventBuilder},
     prelude::*,
 };
-use shipyard::Unique;
+use shipyard::{Component, EntityId, IntoWorkload, Unique, World};
 use window_handles::WindowHandle;
 
 mod window_handles;
@@ -24,8 +24,13 @@ impl Plugin for CommonPlugin {
     fn build_plugin(self, builder: &mut WorkloadBuilder) {
         builder
             .insert(Time::default())
+            .insert(FixedTime::default())
             .register_event::<WindowResizeEvent>()
-            .add_workload(First, sys_update_time);
+            .add_workload(
+                First,
+                (sys_update_time, sys_update_fixed_time).into_sequential_workload(),
+            )
+            .set_stage_loop_condition(FixedUpdate, check_fixed_step);
     }
 }
 
@@ -116,6 +121,17 @@ impl<T: Display> Display for Size<T> {
 
 //====================================================================
 
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Clock {
+    // Measure the real time passed between frames
+    #[default]
+    RealTime,
+    // Every frame advances by the same set amount
+    FixedDelta(Duration),
+    // Frames only advance by time given through `Time::advance`
+    Manual,
+}
+
 #[derive(Unique)]
 pub struct Time {
     elapsed: Instant,
@@ -123,26 +139,51 @@ pub struct Time {
     last_frame: Instant,
     delta: Duration,
     delta_seconds: f32,
+
+    clock: Clock,
+    manual_pending: Duration,
+
+    raw_delta: Duration,
+    scale: f32,
+    paused: bool,
+
+    total: Duration,
+    frame_count: u64,
 }
 
 impl Default for Time {
     fn default() -> Self {
+        Self::new(Clock::default())
+    }
+}
+
+#[allow(dead_code)]
+impl Time {
+    pub fn new(clock: Clock) -> Self {
         Self {
             elapsed: Instant::now(),
             last_frame: Instant::now(),
             delta: Duration::ZERO,
             delta_seconds: 0.,
+
+            clock,
+            manual_pending: Duration::ZERO,
+
+            raw_delta: Duration::ZERO,
+            scale: 1.,
+            paused: false,
+
+            total: Duration::ZERO,
+            frame_count: 0,
         }
     }
-}
 
-#[allow(dead_code)]
-impl Time {
     #[inline]
     pub fn elapsed(&self) -> &Instant {
         &self.elapsed
     }
 
+    // Scaled delta. Zero while paused.
     #[inline]
     pub fn delta(&self) -> &Duration {
         &self.delta
@@ -152,18 +193,244 @@ impl Time {
     pub fn delta_seconds(&self) -> f32 {
         self.delta_seconds
     }
+
+    // Delta from the clock before scaling or pausing is applied
+    #[inline]
+    pub fn raw_delta(&self) -> &Duration {
+        &self.raw_delta
+    }
+
+    #[inline]
+    pub fn raw_delta_seconds(&self) -> f32 {
+        self.raw_delta.as_secs_f32()
+    }
+
+    // Sum of all scaled deltas
+    #[inline]
+    pub fn total(&self) -> &Duration {
+        &self.total
+    }
+
+    #[inline]
+    pub fn total_seconds(&self) -> f32 {
+        self.total.as_secs_f32()
+    }
+
+    #[inline]
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
 }
 
-pub fn sys_update_time(mut time: ResMut<Time>) {
-    time.delta = time.last_frame.elapsed();
-    time.delta_seconds = time.delta.as_secs_f32();
+impl Time {
+    #[inline]
+    pub fn clock(&self) -> &Clock {
+        &self.clock
+    }
+
+    #[inline]
+    pub fn set_clock(&mut self, clock: Clock) {
+        self.clock = clock;
+        self.manual_pending = Duration::ZERO;
+    }
+
+    // Queue time to be used for the next frame. Only used with `Clock::Manual`.
+    #[inline]
+    pub fn advance(&mut self, duration: Duration) {
+        if self.clock != Clock::Manual {
+            log::warn!("Advancing time with a non-manual clock has no effect");
+            return;
+        }
+
+        self.manual_pending += duration;
+    }
+
+    #[inline]
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    #[inline]
+    pub fn set_scale(&mut self, scale: f32) {
+        if !scale.is_finite() || scale < 0. {
+            log::warn!("Invalid time scale '{}' provided", scale);
+            return;
+        }
+
+        self.scale = scale;
+    }
 
-    time.last_frame = Instant::now();
+    #[inline]
+    pub fn paused(&self) -> bool {
+        self.paused
+    }
+
+    #[inline]
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    #[inline]
+    pub fn unpause(&mut self) {
+        self.paused = false;
+    }
+
+    #[inline]
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    fn tick(&mut self) {
+        self.raw_delta = match self.clock {
+            Clock::RealTime => self.last_frame.elapsed(),
+            Clock::FixedDelta(delta) => delta,
+            Clock::Manual => std::mem::take(&mut self.manual_pending),
+        };
+
+        // Avoid float rounding on the common unscaled path to keep deltas reproducible
+        self.delta = match (self.paused, self.scale) {
+            (true, _) => Duration::ZERO,
+            (false, 1.) => self.raw_delta,
+            (false, scale) => self.raw_delta.mul_f64(scale as f64),
+        };
+        self.delta_seconds = self.delta.as_secs_f32();
+
+        self.total += self.delta;
+        self.frame_count += 1;
+
+        self.last_frame = Instant::now();
+    }
+}
+
+pub fn sys_update_time(mut time: ResMut<Time>) {
+    time.tick();
 }
 
 //====================================================================
 
+const DEFAULT_FIXED_TIMESTEP: f32 = 1. / 60.;
+const DEFAULT_MAX_FIXED_STEPS: u32 = 8;
+
 #[derive(Unique)]
+pub struct FixedTime {
+    timestep: Duration,
+    accumulator: Duration,
+
+    max_steps: u32,
+    steps: u32,
+
+    alpha: f32,
+}
+
+impl Default for FixedTime {
+    fn default() -> Self {
+        Self::new(Duration::from_secs_f32(DEFAULT_FIXED_TIMESTEP))
+    }
+}
+
+impl FixedTime {
+    pub fn new(timestep: Duration) -> Self {
+        Self {
+            timestep,
+            accumulator: Duration::ZERO,
+            max_steps: DEFAULT_MAX_FIXED_STEPS,
+            steps: 0,
+            alpha: 0.,
+        }
+    }
+
+    #[inline]
+    pub fn with_max_steps(mut self, max_steps: u32) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    #[inline]
+    pub fn timestep(&self) -> &Duration {
+        &self.timestep
+    }
+
+    #[inline]
+    pub fn timestep_seconds(&self) -> f32 {
+        self.timestep.as_secs_f32()
+    }
+
+    #[inline]
+    pub fn set_timestep(&mut self, timestep: Duration) {
+        self.timestep = timestep;
+    }
+
+    #[inline]
+    pub fn max_steps(&self) -> u32 {
+        self.max_steps
+    }
+
+    #[inline]
+    pub fn set_max_steps(&mut self, max_steps: u32) {
+        self.max_steps = max_steps;
+    }
+
+    #[inline]
+    pub fn accumulator(&self) -> &Duration {
+        &self.accumulator
+    }
+
+    // Number of fixed steps that have run so far this frame
+    #[inline]
+    pub fn steps(&self) -> u32 {
+        self.steps
+    }
+
+    // Fraction of a timestep left in the accumulator after the fixed steps have run.
+    // Use to interpolate between the previous and current fixed states when rendering.
+    #[inline]
+    pub fn alpha(&self) -> f32 {
+        self.alpha
+    }
+
+    fn accumulate(&mut self, delta: Duration) {
+        self.steps = 0;
+
+        // Cap the accumulator so a long frame can't cause a spiral of ever increasing steps
+        let max_accumulated = self.timestep * self.max_steps;
+        self.accumulator = (self.accumulator + delta).min(max_accumulated);
+    }
+
+    fn expend_step(&mut self) -> bool {
+        if !self.timestep.is_zero() && self.accumulator >= self.timestep {
+            self.accumulator -= self.timestep;
+            self.steps += 1;
+            return true;
+        }
+
+        self.alpha = match self.timestep.is_zero() {
+            true => 0.,
+            false => self.accumulator.as_secs_f32() / self.timestep.as_secs_f32(),
+        };
+
+        false
+    }
+}
+
+pub fn sys_update_fixed_time(time: Res<Time>, mut fixed: ResMut<FixedTime>) {
+    fixed.accumulate(time.delta);
+}
+
+pub fn check_fixed_step(world: &World) -> bool {
+    world.run(|mut fixed: ResMut<FixedTime>| fixed.expend_step())
+}
+
+//====================================================================
+
+// Window uniques (WindowRaw, WindowSize) describe the primary window.
+// Every window, including the primary one, is also an entity with the same types as components.
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrimaryWindow;
+
+//--------------------------------------------------
+
+#[derive(Unique, Component)]
 pub struct WindowRaw {
     window: WasmWrapper<Arc<dyn WindowHandle>>,
     size: Size<u32>,
@@ -191,7 +458,7 @@ impl WindowRaw {
 
 //====================================================================
 
-#[derive(Unique)]
+#[derive(Unique, Component, Debug, Clone, Copy)]
 pub struct WindowSize(Size<u32>);
 
 impl WindowSize {
@@ -227,18 +494,238 @@ impl WindowSize {
 }
 
 #[derive(Event, Debug)]
-pub struct WindowResizeEvent(Size<u32>);
+pub struct WindowResizeEvent {
+    window: EntityId,
+    size: Size<u32>,
+    primary: bool,
+}
 
 impl WindowResizeEvent {
     #[inline]
-    pub fn new(new_size: Size<u32>) -> Self {
-        Self(new_size)
+    pub fn new(window: EntityId, new_size: Size<u32>, primary: bool) -> Self {
+        Self {
+            window,
+            size: new_size,
+            primary,
+        }
+    }
+
+    #[inline]
+    pub fn window(&self) -> EntityId {
+        self.window
     }
 
     #[inline]
     pub fn size(&self) -> Size<u32> {
-        self.0
+        self.size
+    }
+
+    #[inline]
+    pub fn is_primary(&self) -> bool {
+        self.primary
     }
 }
 
 //====================================================================
+
+// Settings for the primary window and its surface. Insert while building the app to
+// override the defaults - the runner reads it when creating the window.
+#[derive(Unique, Debug, Clone, PartialEq)]
+pub struct WindowDescriptor {
+    pub title: String,
+    // None lets the platform choose
+    pub size: Option<Size<u32>>,
+    pub min_size: Option<Size<u32>>,
+    pub max_size: Option<Size<u32>>,
+    pub mode: WindowMode,
+    pub resizable: bool,
+    pub decorations: bool,
+    pub icon: Option<WindowIcon>,
+    // Id of the element the canvas is added to on web
+    pub canvas_id: String,
+    pub present_mode: PresentMode,
+}
+
+impl Default for WindowDescriptor {
+    fn default() -> Self {
+        Self {
+            title: "Feathered".into(),
+            size: None,
+            min_size: None,
+            max_size: None,
+            mode: WindowMode::default(),
+            resizable: true,
+            decorations: true,
+            icon: None,
+            canvas_id: "feathered_app".into(),
+            present_mode: PresentMode::default(),
+        }
+    }
+}
+
+impl WindowDescriptor {
+    #[inline]
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    #[inline]
+    pub fn with_size(mut self, size: Size<u32>) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    #[inline]
+    pub fn with_min_size(mut self, size: Size<u32>) -> Self {
+        self.min_size = Some(size);
+        self
+    }
+
+    #[inline]
+    pub fn with_max_size(mut self, size: Size<u32>) -> Self {
+        self.max_size = Some(size);
+        self
+    }
+
+    #[inline]
+    pub fn with_mode(mut self, mode: WindowMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    #[inline]
+    pub fn with_resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    #[inline]
+    pub fn with_decorations(mut self, decorations: bool) -> Self {
+        self.decorations = decorations;
+        self
+    }
+
+    #[inline]
+    pub fn with_icon(mut self, icon: WindowIcon) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    #[inline]
+    pub fn with_canvas_id(mut self, canvas_id: impl Into<String>) -> Self {
+        self.canvas_id = canvas_id.into();
+        self
+    }
+
+    #[inline]
+    pub fn with_present_mode(mut self, present_mode: PresentMode) -> Self {
+        self.present_mode = present_mode;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum WindowMode {
+    #[default]
+    Windowed,
+    BorderlessFullscreen,
+    // Uses the monitor's largest video mode. Falls back to borderless if none are available.
+    ExclusiveFullscreen,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PresentMode {
+    AutoVsync,
+    #[default]
+    AutoNoVsync,
+    Fifo,
+    FifoRelaxed,
+    Immediate,
+    Mailbox,
+}
+
+// 8 bit RGBA pixels, row by row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowIcon {
+    pub rgba: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl WindowIcon {
+    #[inline]
+    pub fn new(rgba: Vec<u8>, width: u32, height: u32) -> Self {
+        Self {
+            rgba,
+            width,
+            height,
+        }
+    }
+}
+
+//====================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{Clock, Duration, Time};
+
+    const FRAME: Duration = Duration::from_millis(100);
+
+    #[test]
+    fn scaling_and_pausing_only_affect_delta() {
+        let mut time = Time::new(Clock::Manual);
+
+        time.advance(FRAME);
+        time.tick();
+        assert_eq!(*time.delta(), FRAME);
+        assert_eq!(*time.raw_delta(), FRAME);
+
+        time.set_scale(0.5);
+        time.advance(FRAME);
+        time.tick();
+        assert_eq!(*time.delta(), FRAME / 2);
+        assert_eq!(*time.raw_delta(), FRAME);
+
+        // Invalid scales are ignored
+        time.set_scale(-1.);
+        assert_eq!(time.scale(), 0.5);
+
+        time.pause();
+        time.advance(FRAME);
+        time.tick();
+        assert_eq!(*time.delta(), Duration::ZERO);
+        assert_eq!(time.delta_seconds(), 0.);
+        assert_eq!(*time.raw_delta(), FRAME);
+
+        // Total only counts scaled time
+        assert_eq!(*time.total(), FRAME + FRAME / 2);
+        assert_eq!(time.frame_count(), 3);
+    }
+
+    #[test]
+    fn manual_clocks_only_use_advanced_time() {
+        let mut time = Time::new(Clock::Manual);
+
+        time.advance(FRAME);
+        time.advance(FRAME);
+        time.tick();
+        assert_eq!(*time.delta(), FRAME * 2);
+
+        time.tick();
+        assert_eq!(*time.delta(), Duration::ZERO);
+    }
+
+    #[test]
+    fn fixed_delta_clocks_ignore_advance() {
+        let mut time = Time::new(Clock::FixedDelta(FRAME));
+
+        time.tick();
+        time.advance(Duration::from_secs(1));
+        time.tick();
+
+        assert_eq!(*time.delta(), FRAME);
+        assert_eq!(*time.raw_delta(), FRAME);
+        assert_eq!(*time.total(), FRAME * 2);
+    }
+}
@@ -16,28 +16,28 @@ mod send_sync {
 
     impl<T: WasmNotSend + WasmNotSync> WasmNotSendSync for T {}
 
-    #[cfg(any(not(target_arch = "wasm32"),))]
+    #[cfg(not(target_arch = "wasm32"))]
     pub trait WasmNotSend: Send {}
 
-    #[cfg(any(not(target_arch = "wasm32"),))]
+    #[cfg(not(target_arch = "wasm32"))]
     impl<T: Send> WasmNotSend for T {}
 
-    #[cfg(not(any(not(target_arch = "wasm32"),)))]
+    #[cfg(target_arch = "wasm32")]
     pub trait WasmNotSend {}
 
-    #[cfg(not(any(not(target_arch = "wasm32"),)))]
+    #[cfg(target_arch = "wasm32")]
     impl<T> WasmNotSend for T {}
 
-    #[cfg(any(not(target_arch = "wasm32"),))]
+    #[cfg(not(target_arch = "wasm32"))]
     pub trait WasmNotSync: Sync {}
 
-    #[cfg(any(not(target_arch = "wasm32"),))]
-
+    #[cfg(not(target_arch = "wasm32"))]
     impl<T: Sync> WasmNotSync for T {}
-    #[cfg(not(any(not(target_arch = "wasm32"),)))]
+
+    #[cfg(target_arch = "wasm32")]
     pub trait WasmNotSync {}
 
-    #[cfg(not(any(not(target_arch = "wasm32"),)))]
+    #[cfg(target_arch = "wasm32")]
     impl<T> WasmNotSync for T {}
 }
 
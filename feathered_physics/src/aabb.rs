@@ -1,17 +1,58 @@
 //====================================================================
 
-use shipyard::Component;
+use feathered_shipyard::{
+    prelude::*,
+    scene::{EntityMap, SceneBuilder, SceneComponent},
+};
+use feathered_spatial::{
+    bounds::{Aabb, BoundsBuilder, ComputeBounds},
+    SpatialPlugin,
+};
+use shipyard::AllStorages;
+
+use crate::CollisionMesh;
 
 //====================================================================
 
-#[derive(Component)]
-pub struct BuiltSimpleCollision {
-    start_x: f32,
-    end_x: f32,
-    start_y: f32,
-    end_y: f32,
-    start_z: f32,
-    end_z: f32,
+// Gives every entity with a collision mesh an `Aabb` (and so world bounds) for broadphase checks.
+// Also lets collision meshes be saved in scenes.
+pub struct CollisionBoundsPlugin;
+
+impl Plugin for CollisionBoundsPlugin {
+    fn build_plugin(self, builder: &mut WorkloadBuilder) {
+        builder
+            .register_scene_component::<CollisionMesh>()
+            .compute_bounds::<CollisionMesh>();
+    }
+
+    fn dependencies(&self) -> Vec<PluginDependency> {
+        vec![PluginDependency::plugin::<SpatialPlugin>()]
+    }
+}
+
+impl ComputeBounds for CollisionMesh {
+    #[inline]
+    fn compute_aabb(&self) -> Option<Aabb> {
+        Aabb::from_points(self.points.iter().copied())
+    }
+}
+
+impl SceneComponent for CollisionMesh {
+    const NAME: &'static str = "CollisionMesh";
+    type Data = Vec<glam::Vec3>;
+
+    #[inline]
+    fn save(&self, _: &AllStorages) -> Option<Self::Data> {
+        Some(self.points.clone())
+    }
+
+    #[inline]
+    fn load(data: Self::Data, _: &EntityMap, _: &AllStorages) -> Option<Self> {
+        match data.is_empty() {
+            true => None,
+            false => Some(CollisionMesh { points: data }),
+        }
+    }
 }
 
 //====================================================================
@@ -9,6 +9,7 @@ use crate::CollisionMesh;
 
 //====================================================================
 
+#[allow(dead_code)]
 fn sys_rebuild_built_complex_collision(
     v_global: View<GlobalTransform>,
     v_collision: View<CollisionMesh, track::InsertionAndModification>,
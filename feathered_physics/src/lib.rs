@@ -11,8 +11,7 @@ pub mod gjk;
 //====================================================================
 
 #[derive(Component, Debug)]
-#[track(Insertion)]
-#[track(Modification)]
+#[track(Insertion, Modification)]
 pub struct CollisionMesh {
     points: Vec<glam::Vec3>,
 }
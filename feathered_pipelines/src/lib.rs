@@ -1,5 +1,12 @@
 //====================================================================
 
+use std::collections::HashMap;
+
+use feathered_render_tools::texture::{LoadedTexture, TextureId};
+use feathered_shipyard::prelude::*;
+use model_renderer::{LoadedMesh, MeshId};
+use shipyard::Unique;
+
 pub mod model_renderer;
 pub mod texture_renderer;
 
@@ -9,7 +16,63 @@ pub mod prelude {
     pub use crate::{
         model_renderer::{Mesh, Model, ModelRendererPlugin},
         texture_renderer::{Sprite, TextureRenderer},
+        SceneAssets,
     };
 }
 
 //====================================================================
+
+// Named textures and meshes. Sprites and models are saved to scenes by these names,
+// so any assets they use need adding here before saving or loading.
+#[derive(Unique, Default)]
+pub struct SceneAssets {
+    textures: HashMap<String, LoadedTexture>,
+    meshes: HashMap<String, LoadedMesh>,
+}
+
+impl SceneAssets {
+    #[inline]
+    pub fn add_texture(&mut self, name: impl Into<String>, texture: LoadedTexture) -> &mut Self {
+        self.textures.insert(name.into(), texture);
+        self
+    }
+
+    #[inline]
+    pub fn add_mesh(&mut self, name: impl Into<String>, mesh: LoadedMesh) -> &mut Self {
+        self.meshes.insert(name.into(), mesh);
+        self
+    }
+
+    #[inline]
+    pub fn texture(&self, name: &str) -> Option<&LoadedTexture> {
+        self.textures.get(name)
+    }
+
+    #[inline]
+    pub fn mesh(&self, name: &str) -> Option<&LoadedMesh> {
+        self.meshes.get(name)
+    }
+
+    pub fn texture_name(&self, id: TextureId) -> Option<&str> {
+        self.textures
+            .iter()
+            .find(|(_, texture)| texture.id() == id)
+            .map(|(name, _)| name.as_str())
+    }
+
+    pub fn mesh_name(&self, id: MeshId) -> Option<&str> {
+        self.meshes
+            .iter()
+            .find(|(_, mesh)| mesh.id() == id)
+            .map(|(name, _)| name.as_str())
+    }
+}
+
+// Shared between the renderer plugins
+pub(crate) fn insert_scene_assets(builder: &mut WorkloadBuilder) {
+    if builder.get_world().borrow::<Res<SceneAssets>>().is_err() {
+        builder.insert(SceneAssets::default());
+    }
+}
+
+//====================================================================
@@ -10,22 +10,43 @@ use feathered_render_tools::{
     camera::Camera3d,
     shared::{ModelVertex, SharedRenderResources},
     texture::{LoadedTexture, TextureId},
-    tools, Device, Queue, RenderPass, SurfaceConfig, Vertex,
+    tools, Device, FullRenderToolsPlugin, Queue, RenderPass, SurfaceConfig, Vertex,
 };
-use feathered_shipyard::prelude::*;
-use feathered_spatial::GlobalTransform;
-use shipyard::{AllStoragesView, Component, IntoIter, Unique};
+use feathered_shipyard::{
+    prelude::*,
+    scene::{EntityMap, SceneBuilder, SceneComponent},
+};
+use feathered_spatial::{
+    bounds::{Aabb, BoundsBuilder, ComputeBounds},
+    GlobalTransform, SpatialPlugin, TransformBuilder,
+};
+use serde::{Deserialize, Serialize};
+use shipyard::{AllStorages, AllStoragesView, Component, IntoIter, Unique};
+
+use crate::SceneAssets;
 
 //====================================================================
 
 pub struct ModelRendererPlugin;
 impl Plugin for ModelRendererPlugin {
     fn build_plugin(self, builder: &mut WorkloadBuilder) {
+        crate::insert_scene_assets(builder);
+
         builder
+            .require_transform::<Model>()
+            .compute_bounds::<Model>()
+            .register_scene_component::<Model>()
             .add_workload_pre(Setup, sys_setup_renderer)
             .add_workload(RenderPrep, sys_prep_renderer)
             .add_workload(Render, sys_render);
     }
+
+    fn dependencies(&self) -> Vec<PluginDependency> {
+        vec![
+            PluginDependency::plugin::<FullRenderToolsPlugin>(),
+            PluginDependency::plugin::<SpatialPlugin>(),
+        ]
+    }
 }
 
 //====================================================================
@@ -77,6 +98,7 @@ pub struct Mesh {
     vertex_buffer: WasmWrapper<wgpu::Buffer>,
     index_buffer: WasmWrapper<wgpu::Buffer>,
     index_count: u32,
+    bounds: Option<Aabb>,
 }
 
 impl Mesh {
@@ -84,18 +106,27 @@ impl Mesh {
         let vertex_buffer = tools::buffer(device, tools::BufferType::Vertex, "Mesh", vertices);
         let index_buffer = tools::buffer(device, tools::BufferType::Index, "Mesh", indices);
         let index_count = indices.len() as u32;
+        let bounds = Aabb::from_points(vertices.iter().map(|vertex| vertex.pos));
 
         Self {
             vertex_buffer: WasmWrapper::new(vertex_buffer),
             index_buffer: WasmWrapper::new(index_buffer),
             index_count,
+            bounds,
         }
     }
+
+    // Local space bounds of the vertices. None if the mesh has no vertices.
+    #[inline]
+    pub fn bounds(&self) -> Option<&Aabb> {
+        self.bounds.as_ref()
+    }
 }
 
 //--------------------------------------------------
 
 #[derive(Component, Clone)]
+#[track(Insertion, Modification)]
 pub struct Model {
     pub meshes: Vec<(LoadedMesh, LoadedTexture)>,
     pub color: [f32; 4],
@@ -125,6 +156,79 @@ impl Model {
     }
 }
 
+// Models are saved with the names of their meshes and textures in `SceneAssets`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ModelData {
+    pub meshes: Vec<(String, String)>,
+    pub color: [f32; 4],
+    pub scale: glam::Vec3,
+}
+
+impl SceneComponent for Model {
+    const NAME: &'static str = "Model";
+    type Data = ModelData;
+
+    fn save(&self, all_storages: &AllStorages) -> Option<Self::Data> {
+        let assets = all_storages.borrow::<Res<SceneAssets>>().ok()?;
+
+        let meshes = self
+            .meshes
+            .iter()
+            .map(|(mesh, texture)| {
+                match (
+                    assets.mesh_name(mesh.id()),
+                    assets.texture_name(texture.id()),
+                ) {
+                    (Some(mesh), Some(texture)) => Some((mesh.to_string(), texture.to_string())),
+                    _ => {
+                        log::warn!(
+                            "Unable to save model - mesh {} or texture {} has no name in scene assets",
+                            mesh.id(),
+                            texture.id()
+                        );
+                        None
+                    }
+                }
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(ModelData {
+            meshes,
+            color: self.color,
+            scale: self.scale,
+        })
+    }
+
+    fn load(data: Self::Data, _: &EntityMap, all_storages: &AllStorages) -> Option<Self> {
+        let assets = all_storages.borrow::<Res<SceneAssets>>().ok()?;
+
+        let meshes = data
+            .meshes
+            .iter()
+            .map(|(mesh, texture)| {
+                Some((assets.mesh(mesh)?.clone(), assets.texture(texture)?.clone()))
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(Model {
+            meshes,
+            color: data.color,
+            scale: data.scale,
+        })
+    }
+}
+
+impl ComputeBounds for Model {
+    fn compute_aabb(&self) -> Option<Aabb> {
+        self.meshes
+            .iter()
+            .filter_map(|(mesh, _)| mesh.mesh().bounds())
+            .copied()
+            .reduce(|acc, bounds| acc.merge(&bounds))
+            .map(|bounds| Aabb::new(bounds.min * self.scale, bounds.max * self.scale))
+    }
+}
+
 //====================================================================
 
 #[repr(C)]
@@ -230,9 +334,10 @@ fn sys_prep_renderer(
             .fold(HashMap::new(), |mut acc, (transform, model)| {
                 model.meshes.iter().for_each(|(mesh, texture)| {
                     let mesh_entry = acc.entry(mesh.id).or_insert_with(|| {
-                        if !renderer.mesh_storage.contains_key(&mesh.id) {
-                            renderer.mesh_storage.insert(mesh.id, mesh.clone());
-                        }
+                        renderer
+                            .mesh_storage
+                            .entry(mesh.id)
+                            .or_insert_with(|| mesh.clone());
 
                         meshes_used.insert(mesh.id);
 
@@ -245,11 +350,10 @@ fn sys_prep_renderer(
                     mesh_entry
                         .entry(texture.id())
                         .or_insert_with(|| {
-                            if !renderer.texture_storage.contains_key(&texture.id()) {
-                                renderer
-                                    .texture_storage
-                                    .insert(texture.id(), texture.clone());
-                            }
+                            renderer
+                                .texture_storage
+                                .entry(texture.id())
+                                .or_insert_with(|| texture.clone());
 
                             textures_used.insert(texture.id());
 
@@ -273,7 +377,7 @@ fn sys_prep_renderer(
             renderer
                 .instances
                 .entry(mesh_id)
-                .or_insert(HashMap::default())
+                .or_default()
                 .entry(texture_id)
                 .and_modify(|instance| instance.update(device.inner(), queue.inner(), &raw))
                 .or_insert_with(|| tools::InstanceBuffer::new(device.inner(), &raw));
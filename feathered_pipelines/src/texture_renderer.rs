@@ -10,33 +10,107 @@ use feathered_render_tools::{
         TEXTURE_RECT_VERTICES,
     },
     texture::{LoadedTexture, TextureId},
-    tools, Device, Queue, RenderPass, SurfaceConfig, Vertex,
+    tools, Device, FullRenderToolsPlugin, Queue, RenderPass, SurfaceConfig, Vertex,
 };
-use feathered_shipyard::prelude::*;
-use feathered_spatial::GlobalTransform;
-use shipyard::{AllStoragesView, Component, IntoIter, Unique, View};
+use feathered_shipyard::{
+    prelude::*,
+    scene::{EntityMap, SceneBuilder, SceneComponent},
+};
+use feathered_spatial::{
+    bounds::{Aabb, BoundsBuilder, ComputeBounds},
+    GlobalTransform, SpatialPlugin, TransformBuilder,
+};
+use serde::{Deserialize, Serialize};
+use shipyard::{AllStorages, AllStoragesView, Component, IntoIter, Unique, View};
+
+use crate::SceneAssets;
 
 //====================================================================
 
 pub struct TextureRendererPlugin;
 impl Plugin for TextureRendererPlugin {
     fn build_plugin(self, builder: &mut WorkloadBuilder) {
+        crate::insert_scene_assets(builder);
+
         builder
+            .require_transform::<Sprite>()
+            .compute_bounds::<Sprite>()
+            .register_scene_component::<Sprite>()
             .add_workload_pre(Setup, sys_setup_renderer)
             .add_workload(RenderPrep, sys_prep_renderer)
             .add_workload(Render, sys_render);
     }
+
+    fn dependencies(&self) -> Vec<PluginDependency> {
+        vec![
+            PluginDependency::plugin::<FullRenderToolsPlugin>(),
+            PluginDependency::plugin::<SpatialPlugin>(),
+        ]
+    }
 }
 
 //====================================================================
 
 #[derive(Component, Debug, Clone)]
+#[track(Insertion, Modification)]
 pub struct Sprite {
     pub texture: LoadedTexture,
     pub size: glam::Vec2,
     pub color: [f32; 4],
 }
 
+impl ComputeBounds for Sprite {
+    // Matches the quad drawn by the texture shader
+    fn compute_aabb(&self) -> Option<Aabb> {
+        let half_size = (self.size * 0.5).extend(0.);
+        Some(Aabb::new(
+            glam::Vec3::Z - half_size,
+            glam::Vec3::Z + half_size,
+        ))
+    }
+}
+
+// Sprites are saved with the name of their texture in `SceneAssets`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SpriteData {
+    pub texture: String,
+    pub size: glam::Vec2,
+    pub color: [f32; 4],
+}
+
+impl SceneComponent for Sprite {
+    const NAME: &'static str = "Sprite";
+    type Data = SpriteData;
+
+    fn save(&self, all_storages: &AllStorages) -> Option<Self::Data> {
+        let assets = all_storages.borrow::<Res<SceneAssets>>().ok()?;
+
+        let Some(texture) = assets.texture_name(self.texture.id()) else {
+            log::warn!(
+                "Unable to save sprite - texture {} has no name in scene assets",
+                self.texture.id()
+            );
+            return None;
+        };
+
+        Some(SpriteData {
+            texture: texture.to_string(),
+            size: self.size,
+            color: self.color,
+        })
+    }
+
+    fn load(data: Self::Data, _: &EntityMap, all_storages: &AllStorages) -> Option<Self> {
+        let assets = all_storages.borrow::<Res<SceneAssets>>().ok()?;
+
+        Some(Sprite {
+            texture: assets.texture(&data.texture)?.clone(),
+            size: data.size,
+            color: data.color,
+        })
+    }
+}
+
 #[repr(C)]
 #[derive(bytemuck::Pod, bytemuck::Zeroable, Clone, Copy, Debug)]
 struct TextureInstance {
@@ -154,11 +228,7 @@ fn sys_prep_renderer(
     v_global: View<GlobalTransform>,
     v_sprite: View<Sprite>,
 ) {
-    let mut previous = renderer
-        .instances
-        .keys()
-        .map(|id| *id)
-        .collect::<HashSet<_>>();
+    let mut previous = renderer.instances.keys().copied().collect::<HashSet<_>>();
 
     let instances =
         (&v_global, &v_sprite)
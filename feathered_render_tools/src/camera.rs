@@ -108,6 +108,7 @@ impl CameraWgpu {
 
 //====================================================================
 
+#[allow(clippy::wrong_self_convention)]
 pub trait CameraUniform {
     fn into_uniform(&self) -> CameraUniformRaw;
 }
@@ -162,7 +163,7 @@ impl Default for OrthographicCamera {
 
 impl CameraUniform for OrthographicCamera {
     fn into_uniform(&self) -> CameraUniformRaw {
-        CameraUniformRaw::new(self.get_projection(), self.translation.into())
+        CameraUniformRaw::new(self.get_projection(), self.translation)
     }
 }
 
@@ -239,7 +240,7 @@ impl Default for PerspectiveCamera {
     fn default() -> Self {
         Self {
             up: glam::Vec3::Y,
-            aspect: 1.7777777778,
+            aspect: 1.777_777_8,
             fovy: 45.,
             z_near: 0.1,
             z_far: 1000000.,
@@ -252,7 +253,7 @@ impl Default for PerspectiveCamera {
 
 impl CameraUniform for PerspectiveCamera {
     fn into_uniform(&self) -> CameraUniformRaw {
-        CameraUniformRaw::new(self.get_projection(), self.translation.into())
+        CameraUniformRaw::new(self.get_projection(), self.translation)
     }
 }
 
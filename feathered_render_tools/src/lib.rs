@@ -1,12 +1,18 @@
 //====================================================================
 
-use feathered_common::{Size, WasmWrapper, WindowRaw, WindowResizeEvent};
+use feathered_common::{
+    CommonPlugin, PresentMode, PrimaryWindow, Size, WasmWrapper, WindowDescriptor, WindowRaw,
+    WindowResizeEvent, WindowSize,
+};
 use feathered_shipyard::{
     events::{EventBuilder, EventReader, ReadEvents},
     prelude::*,
 };
 use pollster::FutureExt;
-use shipyard::{AllStoragesView, IntoWorkload, SystemModificator, Unique, WorkloadModificator};
+use shipyard::{
+    AddComponent, AllStoragesView, Component, Get, IntoIter, IntoWithId, IntoWorkload,
+    SystemModificator, Unique, WorkloadModificator,
+};
 use texture::DepthTexture;
 
 pub mod camera;
@@ -61,7 +67,18 @@ impl Plugin for RenderComponentsPlugin {
                 Setup,
                 sys_setup_renderer_components.tag(SetupRendererComponents),
             )
-            .event_workload::<WindowResizeEvent>(First, sys_resize_surface.into_workload());
+            .event_workload::<WindowResizeEvent>(
+                First,
+                (sys_resize_surface, sys_resize_window_surfaces).into_workload(),
+            )
+            .add_workload(First, sys_setup_window_surfaces);
+    }
+
+    fn dependencies(&self) -> Vec<PluginDependency> {
+        vec![
+            PluginDependency::plugin::<CommonPlugin>(),
+            PluginDependency::unique::<WindowRaw>(),
+        ]
     }
 }
 
@@ -122,11 +139,29 @@ impl Queue {
     }
 }
 
+#[derive(Unique)]
+pub struct Instance(WasmWrapper<wgpu::Instance>);
+impl Instance {
+    #[inline]
+    pub fn inner(&self) -> &wgpu::Instance {
+        &self.0
+    }
+}
+
+#[derive(Unique)]
+pub struct Adapter(WasmWrapper<wgpu::Adapter>);
+impl Adapter {
+    #[inline]
+    pub fn inner(&self) -> &wgpu::Adapter {
+        &self.0
+    }
+}
+
 #[derive(Unique)]
 pub struct Surface(WasmWrapper<wgpu::Surface<'static>>);
 impl Surface {
     #[inline]
-    pub fn inner(&self) -> &wgpu::Surface {
+    pub fn inner(&self) -> &wgpu::Surface<'_> {
         &self.0
     }
 }
@@ -186,7 +221,55 @@ pub fn sys_setup_renderer_components(all_storages: AllStoragesView, window: Res<
         .block_on()
         .unwrap();
 
-    let surface_capabilities = surface.get_capabilities(&adapter);
+    let present_mode = all_storages
+        .borrow::<Res<WindowDescriptor>>()
+        .map(|descriptor| descriptor.present_mode)
+        .unwrap_or_default();
+
+    let config = surface_config(&surface, &adapter, size, to_wgpu_present_mode(present_mode));
+    surface.configure(&device, &config);
+
+    all_storages
+        .insert(Instance(WasmWrapper::new(instance)))
+        .insert(Adapter(WasmWrapper::new(adapter)))
+        .insert(Device(WasmWrapper::new(device)))
+        .insert(Queue(WasmWrapper::new(queue)))
+        .insert(Surface(WasmWrapper::new(surface)))
+        .insert(SurfaceConfig(config));
+}
+
+fn to_wgpu_present_mode(present_mode: PresentMode) -> wgpu::PresentMode {
+    match present_mode {
+        PresentMode::AutoVsync => wgpu::PresentMode::AutoVsync,
+        PresentMode::AutoNoVsync => wgpu::PresentMode::AutoNoVsync,
+        PresentMode::Fifo => wgpu::PresentMode::Fifo,
+        PresentMode::FifoRelaxed => wgpu::PresentMode::FifoRelaxed,
+        PresentMode::Immediate => wgpu::PresentMode::Immediate,
+        PresentMode::Mailbox => wgpu::PresentMode::Mailbox,
+    }
+}
+
+fn surface_config(
+    surface: &wgpu::Surface,
+    adapter: &wgpu::Adapter,
+    size: Size<u32>,
+    present_mode: wgpu::PresentMode,
+) -> wgpu::SurfaceConfiguration {
+    let surface_capabilities = surface.get_capabilities(adapter);
+
+    // Auto modes always fall back to something supported. Fifo is the only other mode every
+    // surface supports.
+    let present_mode = match present_mode {
+        wgpu::PresentMode::AutoVsync | wgpu::PresentMode::AutoNoVsync => present_mode,
+        _ if surface_capabilities.present_modes.contains(&present_mode) => present_mode,
+        _ => {
+            log::warn!(
+                "Present mode {:?} not supported by surface. Using Fifo.",
+                present_mode
+            );
+            wgpu::PresentMode::Fifo
+        }
+    };
 
     let surface_format = surface_capabilities
         .formats
@@ -195,24 +278,16 @@ pub fn sys_setup_renderer_components(all_storages: AllStoragesView, window: Res<
         .copied()
         .unwrap_or(surface_capabilities.formats[0]);
 
-    let config = wgpu::SurfaceConfiguration {
+    wgpu::SurfaceConfiguration {
         usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
         format: surface_format,
         width: size.width,
         height: size.height,
-        present_mode: wgpu::PresentMode::AutoNoVsync,
+        present_mode,
         desired_maximum_frame_latency: 2,
         alpha_mode: surface_capabilities.alpha_modes[0],
         view_formats: vec![],
-    };
-
-    surface.configure(&device, &config);
-
-    all_storages
-        .insert(Device(WasmWrapper::new(device)))
-        .insert(Queue(WasmWrapper::new(queue)))
-        .insert(Surface(WasmWrapper::new(surface)))
-        .insert(SurfaceConfig(config));
+    }
 }
 
 pub fn sys_resize_surface(
@@ -221,13 +296,100 @@ pub fn sys_resize_surface(
     mut config: ResMut<SurfaceConfig>,
     window_resize: EventReader<WindowResizeEvent>,
 ) {
-    if let Some(new_size) = window_resize.last() {
+    if let Some(new_size) = window_resize.iter().rev().find(|event| event.is_primary()) {
         let size = new_size.size();
         config.resize(size);
         surface.inner().configure(device.inner(), config.inner());
     }
 }
 
+//--------------------------------------------------
+
+// Surface for a window other than the primary one. The main render pass only draws to the
+// primary window, other windows are drawn to by getting the current texture of their surface.
+#[derive(Component)]
+pub struct WindowSurface {
+    surface: WasmWrapper<wgpu::Surface<'static>>,
+    config: wgpu::SurfaceConfiguration,
+}
+
+impl WindowSurface {
+    #[inline]
+    pub fn inner(&self) -> &wgpu::Surface<'_> {
+        &self.surface
+    }
+
+    #[inline]
+    pub fn config(&self) -> &wgpu::SurfaceConfiguration {
+        &self.config
+    }
+
+    pub fn resize(&mut self, device: &wgpu::Device, size: Size<u32>) {
+        self.config.width = size.width;
+        self.config.height = size.height;
+        self.surface.configure(device, &self.config);
+    }
+}
+
+pub fn sys_setup_window_surfaces(
+    (instance, adapter, device): (Res<Instance>, Res<Adapter>, Res<Device>),
+    primary_config: Res<SurfaceConfig>,
+    v_window: View<WindowRaw>,
+    v_size: View<WindowSize>,
+    v_primary: View<PrimaryWindow>,
+    mut vm_surface: ViewMut<WindowSurface>,
+) {
+    let new_surfaces = (&v_window, &v_size, !&v_primary, !&vm_surface)
+        .iter()
+        .with_id()
+        .filter_map(|(id, (window, size, ..))| {
+            let surface = match instance.inner().create_surface(window.arc().clone()) {
+                Ok(surface) => surface,
+                Err(e) => {
+                    log::error!("Unable to create surface for window {:?} - {}", id, e);
+                    return None;
+                }
+            };
+
+            let config = surface_config(
+                &surface,
+                adapter.inner(),
+                size.size(),
+                primary_config.inner().present_mode,
+            );
+            surface.configure(device.inner(), &config);
+
+            Some((
+                id,
+                WindowSurface {
+                    surface: WasmWrapper::new(surface),
+                    config,
+                },
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    new_surfaces.into_iter().for_each(|(id, surface)| {
+        log::trace!("Created surface for window {:?}", id);
+        vm_surface.add_component_unchecked(id, surface);
+    });
+}
+
+pub fn sys_resize_window_surfaces(
+    device: Res<Device>,
+    mut vm_surface: ViewMut<WindowSurface>,
+    window_resize: EventReader<WindowResizeEvent>,
+) {
+    window_resize
+        .iter()
+        .filter(|event| !event.is_primary())
+        .for_each(|event| {
+            if let Ok(mut surface) = (&mut vm_surface).get(event.window()) {
+                surface.resize(device.inner(), event.size());
+            }
+        });
+}
+
 //====================================================================
 
 #[derive(Unique)]
@@ -328,19 +490,18 @@ impl RenderEncoder {
         self.surface_texture.take().present();
     }
 
-    pub fn begin_render_pass(&mut self, desc: RenderPassDesc) -> wgpu::RenderPass {
+    pub fn begin_render_pass(&mut self, desc: RenderPassDesc) -> wgpu::RenderPass<'_> {
         // Clear the current depth buffer and use it.
-        let depth_stencil_attachment = match desc.use_depth {
-            Some(view) => Some(wgpu::RenderPassDepthStencilAttachment {
-                view,
-                depth_ops: Some(wgpu::Operations {
-                    load: wgpu::LoadOp::Clear(1.),
-                    store: wgpu::StoreOp::Store,
-                }),
-                stencil_ops: None,
-            }),
-            None => None,
-        };
+        let depth_stencil_attachment =
+            desc.use_depth
+                .map(|view| wgpu::RenderPassDepthStencilAttachment {
+                    view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.),
+                        store: wgpu::StoreOp::Store,
+                    }),
+                    stencil_ops: None,
+                });
 
         let load = match desc.clear_color {
             Some(color) => wgpu::LoadOp::Clear(wgpu::Color {
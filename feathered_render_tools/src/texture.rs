@@ -20,7 +20,7 @@ pub struct DepthTexture(pub WasmWrapper<Texture>);
 
 impl DepthTexture {
     pub fn new(device: &wgpu::Device, size: Size<u32>) -> Self {
-        let depth_texture = Texture::create_depth_texture(&device, size, "Main Depth Texture");
+        let depth_texture = Texture::create_depth_texture(device, size, "Main Depth Texture");
 
         Self(WasmWrapper::new(depth_texture))
     }
@@ -50,7 +50,7 @@ pub fn sys_resize_depth_texture(
     mut depth_texture: ResMut<DepthTexture>,
     window_resize: EventReader<WindowResizeEvent>,
 ) {
-    if let Some(size) = window_resize.last() {
+    if let Some(size) = window_resize.iter().rev().find(|event| event.is_primary()) {
         depth_texture.resize(device.inner(), size.size());
     }
 }
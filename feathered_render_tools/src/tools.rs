@@ -9,6 +9,7 @@ use crate::texture::Texture;
 
 //====================================================================
 
+#[derive(Default)]
 pub struct RenderPipelineDescriptor<'a> {
     pub primitive: wgpu::PrimitiveState,
     pub depth_stencil: Option<wgpu::DepthStencilState>,
@@ -18,19 +19,6 @@ pub struct RenderPipelineDescriptor<'a> {
     pub cache: Option<&'a wgpu::PipelineCache>,
 }
 
-impl<'a> Default for RenderPipelineDescriptor<'a> {
-    fn default() -> Self {
-        Self {
-            primitive: wgpu::PrimitiveState::default(),
-            depth_stencil: None,
-            multisample: Default::default(),
-            fragment_targets: None,
-            multiview: None,
-            cache: None,
-        }
-    }
-}
-
 impl RenderPipelineDescriptor<'_> {
     pub fn with_depth_stencil(mut self) -> Self {
         self.depth_stencil = Some(wgpu::DepthStencilState {
@@ -203,7 +191,7 @@ pub fn update_instance_buffer<T: bytemuck::Pod>(
 
     data: &[T],
 ) {
-    if data.len() == 0 {
+    if data.is_empty() {
         // Nothing to update
         if *instance_count != 0 {
             // Empty buffer and reset instance count
@@ -1,24 +1,228 @@
 //====================================================================
 
-use feathered_shipyard::{
-    events::{Event, EventHandle, WriteEvents},
-    ResMut,
+use std::{collections::HashSet, path::PathBuf};
+
+use feathered_shipyard::events::{Event, EventSender, WriteEvents};
+use shipyard::{EntityId, Unique};
+pub use winit::{
+    event::{Ime, MouseButton, TouchPhase},
+    keyboard::{Key, KeyCode, ModifiersState, NamedKey},
 };
-pub use winit::{event::MouseButton, keyboard::KeyCode};
 
 //====================================================================
 
+// 'window' is the window entity the input was sent to
 #[derive(Event, Debug)]
 pub enum WindowInputEvent {
-    KeyInput { key: KeyCode, pressed: bool },
-    MouseInput { button: MouseButton, pressed: bool },
-    CursorMoved { position: (f64, f64) },
-    MouseWheel { delta: (f32, f32) },
-    CursorMotion { delta: (f64, f64) },
+    KeyInput {
+        window: EntityId,
+        key: KeyCode,
+        // Key after the keyboard layout is applied
+        logical_key: Key,
+        pressed: bool,
+        repeat: bool,
+    },
+    // Text produced by a key press. Use for text fields rather than `KeyInput`.
+    Text {
+        window: EntityId,
+        text: String,
+    },
+    Ime {
+        window: EntityId,
+        ime: Ime,
+    },
+    ModifiersChanged {
+        window: EntityId,
+        modifiers: ModifiersState,
+    },
+    MouseInput {
+        window: EntityId,
+        button: MouseButton,
+        pressed: bool,
+    },
+    CursorMoved {
+        window: EntityId,
+        position: (f64, f64),
+    },
+    // Scrolled in lines, usually from a mouse wheel
+    MouseWheel {
+        window: EntityId,
+        delta: (f32, f32),
+    },
+    // Scrolled in pixels, usually from a trackpad
+    MouseWheelPixels {
+        window: EntityId,
+        delta: (f64, f64),
+    },
+    CursorEntered {
+        window: EntityId,
+    },
+    CursorLeft {
+        window: EntityId,
+    },
+    // 'id' stays the same for a finger from 'Started' until 'Ended' or 'Cancelled'
+    Touch {
+        window: EntityId,
+        id: u64,
+        phase: TouchPhase,
+        position: (f64, f64),
+    },
+    FileHovered {
+        window: EntityId,
+        path: PathBuf,
+    },
+    FileHoverCancelled {
+        window: EntityId,
+    },
+    FileDropped {
+        window: EntityId,
+        path: PathBuf,
+    },
+    // Raw device motion, not tied to a window
+    CursorMotion {
+        delta: (f64, f64),
+    },
 }
 
-pub(crate) fn sys_send_event<E: Event>(event: E, mut event_handle: ResMut<EventHandle<E>>) {
-    event_handle.send_event(event);
+//--------------------------------------------------
+
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowFocused {
+    pub window: EntityId,
+    pub focused: bool,
+}
+
+// Sent when the platform suspends the app, such as when it is moved to the background on mobile
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AppSuspended;
+
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AppResumed;
+
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryWarning;
+
+// The user asked to close the window. It closes at the end of the frame unless vetoed
+// through `CloseRequests`. Closing the primary window exits the app.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CloseRequested {
+    pub window: EntityId,
+}
+
+// Send to quit the app at the end of the frame
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AppExit;
+
+//--------------------------------------------------
+
+#[derive(Unique, Debug, Default)]
+pub struct CloseRequests {
+    pending: Vec<EntityId>,
+    vetoed: HashSet<EntityId>,
+}
+
+impl CloseRequests {
+    // Keep the window open for this request
+    #[inline]
+    pub fn veto(&mut self, window: EntityId) {
+        self.vetoed.insert(window);
+    }
+
+    #[inline]
+    pub fn veto_all(&mut self) {
+        self.vetoed.extend(self.pending.iter().copied());
+    }
+
+    #[inline]
+    pub fn pending(&self) -> &[EntityId] {
+        &self.pending
+    }
+
+    #[inline]
+    pub(crate) fn request(&mut self, window: EntityId) {
+        if !self.pending.contains(&window) {
+            self.pending.push(window);
+        }
+    }
+
+    // Windows that weren't vetoed
+    pub(crate) fn take(&mut self) -> Vec<EntityId> {
+        let vetoed = std::mem::take(&mut self.vetoed);
+
+        std::mem::take(&mut self.pending)
+            .into_iter()
+            .filter(|window| {
+                let keep = !vetoed.contains(window);
+                if !keep {
+                    log::info!("Close request for window {:?} vetoed", window);
+                }
+                keep
+            })
+            .collect()
+    }
 }
 
 //====================================================================
+
+pub(crate) fn sys_send_event<E: Event>(event: E, mut sender: EventSender<E>) {
+    sender.send_event(event);
+}
+
+//====================================================================
+
+#[cfg(test)]
+mod tests {
+    use shipyard::{EntityId, World};
+
+    use super::CloseRequests;
+
+    fn windows() -> (EntityId, EntityId) {
+        let mut world = World::new();
+        (world.add_entity(()), world.add_entity(()))
+    }
+
+    #[test]
+    fn vetoed_windows_are_kept_open() {
+        let (first, second) = windows();
+        let mut requests = CloseRequests::default();
+
+        requests.request(first);
+        requests.request(second);
+        requests.veto(first);
+
+        assert_eq!(requests.take(), vec![second]);
+        assert!(requests.pending().is_empty());
+
+        // Vetoes only apply to the requests they were made for
+        requests.request(first);
+        assert_eq!(requests.take(), vec![first]);
+    }
+
+    #[test]
+    fn duplicate_requests_close_once() {
+        let (first, _) = windows();
+        let mut requests = CloseRequests::default();
+
+        requests.request(first);
+        requests.request(first);
+
+        assert_eq!(requests.pending(), &[first]);
+        assert_eq!(requests.take(), vec![first]);
+        assert!(requests.take().is_empty());
+    }
+
+    #[test]
+    fn veto_all_keeps_every_window_open() {
+        let (first, second) = windows();
+        let mut requests = CloseRequests::default();
+
+        requests.request(first);
+        requests.request(second);
+        requests.veto_all();
+
+        assert!(requests.take().is_empty());
+
+        requests.request(second);
+        assert_eq!(requests.take(), vec![second]);
+    }
+}
@@ -0,0 +1,615 @@
+//====================================================================
+
+use std::time::Duration;
+
+use feathered_common::{Clock, Size, Time, WindowSize};
+use feathered_shipyard::{
+    builder::WorkloadBuilder,
+    error::FeatheredError,
+    events::{Event, EventReader, ReadEvents},
+    runner::WorkloadRunner,
+    tools::UniqueTools,
+    ResMut,
+};
+
+use crate::{build_workloads, events};
+
+//====================================================================
+
+const DEFAULT_HEADLESS_SIZE: Size<u32> = Size {
+    width: 1280,
+    height: 720,
+};
+
+// Runs the same stages as `Runner` without creating an event loop or window.
+// Frames are stepped manually, making it usable in tests and on machines with no display.
+pub struct HeadlessRunner {
+    world: shipyard::World,
+    workload_runner: WorkloadRunner,
+    frame: u64,
+    // Set when a stage fails under the 'Stop' error policy. No further frames are run.
+    stopped: Option<FeatheredError>,
+    // Set once an `AppExit` event is sent. No further frames are run.
+    exited: bool,
+}
+
+impl HeadlessRunner {
+    // Panics if the workloads fail to build or prep. See `try_new`.
+    pub fn new<F>(build_app: F) -> Self
+    where
+        F: FnOnce(&mut WorkloadBuilder),
+    {
+        Self::try_new(build_app).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_new<F>(build_app: F) -> Result<Self, FeatheredError>
+    where
+        F: FnOnce(&mut WorkloadBuilder),
+    {
+        let world = shipyard::World::new();
+
+        // Systems such as input processing expect a window size, so provide a virtual one.
+        // Can be replaced inside `build_app`.
+        world.insert(WindowSize::new(DEFAULT_HEADLESS_SIZE));
+
+        let mut workload_runner = build_workloads(&world, build_app)?;
+        workload_runner.prep(&world)?;
+
+        Ok(Self {
+            world,
+            workload_runner,
+            frame: 0,
+            stopped: None,
+            exited: false,
+        })
+    }
+
+    #[inline]
+    pub fn with_frame_delta(mut self, delta: Duration) -> Self {
+        self.set_frame_delta(Some(delta));
+        self
+    }
+
+    #[inline]
+    pub fn world(&self) -> &shipyard::World {
+        &self.world
+    }
+
+    #[inline]
+    pub fn into_world(self) -> shipyard::World {
+        self.world
+    }
+
+    #[inline]
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    #[inline]
+    pub fn stopped(&self) -> Option<&FeatheredError> {
+        self.stopped.as_ref()
+    }
+
+    #[inline]
+    pub fn exited(&self) -> bool {
+        self.exited
+    }
+}
+
+impl HeadlessRunner {
+    // Set the clock used by `Time` for each stepped frame
+    pub fn set_clock(&mut self, clock: Clock) {
+        match self.world.borrow::<ResMut<Time>>() {
+            Ok(mut time) => time.set_clock(clock),
+            Err(_) => log::warn!("Unable to set headless clock - Time unique not found"),
+        }
+    }
+
+    // Step every frame by the given delta. `None` uses real time.
+    #[inline]
+    pub fn set_frame_delta(&mut self, delta: Option<Duration>) {
+        self.set_clock(match delta {
+            Some(delta) => Clock::FixedDelta(delta),
+            None => Clock::RealTime,
+        });
+    }
+
+    // Step a single frame that lasts the given duration
+    pub fn step_by(&mut self, duration: Duration) -> &mut Self {
+        match self.world.borrow::<ResMut<Time>>() {
+            Ok(mut time) => {
+                if *time.clock() != Clock::Manual {
+                    time.set_clock(Clock::Manual);
+                }
+                time.advance(duration);
+            }
+            Err(_) => log::warn!("Unable to advance headless time - Time unique not found"),
+        }
+
+        self.step()
+    }
+
+    #[inline]
+    pub fn send_event<E: Event>(&self, event: E) {
+        self.world.run_with_data(events::sys_send_event, event);
+    }
+
+    pub fn step(&mut self) -> &mut Self {
+        if self.stopped.is_some() || self.exited {
+            return self;
+        }
+
+        match self.workload_runner.run(&self.world) {
+            Ok(()) => self.frame += 1,
+            Err(e) => self.stopped = Some(e),
+        }
+
+        self.exited = self
+            .world
+            .run(|exit: EventReader<events::AppExit>| !exit.is_empty());
+
+        self
+    }
+
+    pub fn step_frames(&mut self, frames: u64) -> &mut Self {
+        (0..frames).for_each(|_| {
+            self.step();
+        });
+        self
+    }
+
+    // Step frames until the condition is met, checking after each frame.
+    // Returns false if the condition wasn't met within `max_frames`.
+    pub fn run_until<C>(&mut self, mut condition: C, max_frames: u64) -> bool
+    where
+        C: FnMut(&shipyard::World) -> bool,
+    {
+        for _ in 0..max_frames {
+            self.step();
+
+            if self.stopped.is_some() {
+                return false;
+            }
+
+            if condition(&self.world) {
+                return true;
+            }
+
+            if self.exited {
+                return false;
+            }
+        }
+
+        false
+    }
+}
+
+//====================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use feathered_common::{CommonPlugin, FixedTime};
+    use feathered_shipyard::{
+        error::ErrorPolicy,
+        events::{Event, EventBuilder, EventReader, ReadEvents},
+        prelude::*,
+        state::{AppState, OnEnter, OnExit, OnUpdate, StateBuilder},
+    };
+    use shipyard::{
+        AllStoragesViewMut, Component, EntitiesView, EntityId, Get, IntoIter, IntoWorkload, Unique,
+    };
+
+    use super::HeadlessRunner;
+
+    #[derive(Event, Debug)]
+    struct TestEvent(u32);
+
+    #[derive(Unique, Default)]
+    struct Counts {
+        fixed_steps: u32,
+        events: Vec<u32>,
+        event_workload_runs: u32,
+    }
+
+    fn sys_count_fixed(mut counts: ResMut<Counts>) {
+        counts.fixed_steps += 1;
+    }
+
+    fn sys_read_events(mut counts: ResMut<Counts>, events: EventReader<TestEvent>) {
+        counts.events.extend(events.iter().map(|event| event.0));
+    }
+
+    fn sys_count_event_workload(mut counts: ResMut<Counts>) {
+        counts.event_workload_runs += 1;
+    }
+
+    fn build_app(builder: &mut WorkloadBuilder) {
+        builder
+            .add_plugin(CommonPlugin)
+            .insert(FixedTime::new(Duration::from_millis(10)))
+            .insert(Counts::default())
+            .register_event::<TestEvent>()
+            .add_workload(FixedUpdate, sys_count_fixed)
+            .add_workload(Update, sys_read_events)
+            .event_workload::<TestEvent>(Update, sys_count_event_workload.into_workload());
+    }
+
+    fn fixed_steps(runner: &HeadlessRunner) -> (u32, u32) {
+        runner
+            .world()
+            .run(|counts: Res<Counts>, fixed: Res<FixedTime>| (counts.fixed_steps, fixed.steps()))
+    }
+
+    #[test]
+    fn fixed_steps_follow_stepped_time() {
+        let mut runner = HeadlessRunner::new(build_app);
+
+        runner.step_by(Duration::from_millis(25));
+        assert_eq!(fixed_steps(&runner), (2, 2));
+
+        // 5ms left over from the last frame
+        runner.step_by(Duration::from_millis(25));
+        assert_eq!(fixed_steps(&runner), (5, 3));
+
+        runner.step_by(Duration::from_millis(4));
+        assert_eq!(fixed_steps(&runner), (5, 0));
+
+        assert_eq!(runner.frame(), 3);
+        assert!(runner.stopped().is_none());
+    }
+
+    #[test]
+    fn sent_events_are_read_once() {
+        let mut runner = HeadlessRunner::new(build_app);
+
+        runner.send_event(TestEvent(1));
+        runner.send_event(TestEvent(2));
+        runner.step();
+
+        runner.send_event(TestEvent(3));
+        runner.step_frames(3);
+
+        let events = runner
+            .world()
+            .run(|counts: Res<Counts>| counts.events.clone());
+        assert_eq!(events, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn event_workloads_only_run_on_new_events() {
+        let mut runner = HeadlessRunner::new(build_app);
+        let runs = |runner: &HeadlessRunner| {
+            runner
+                .world()
+                .run(|counts: Res<Counts>| counts.event_workload_runs)
+        };
+
+        runner.step_frames(2);
+        assert_eq!(runs(&runner), 0);
+
+        // Events are retained for more than a frame, but only trigger the workload once
+        runner.send_event(TestEvent(1));
+        runner.step_frames(3);
+        assert_eq!(runs(&runner), 1);
+
+        runner.send_event(TestEvent(2));
+        runner.send_event(TestEvent(3));
+        runner.step();
+        assert_eq!(runs(&runner), 2);
+    }
+
+    //--------------------------------------------------
+
+    #[derive(Debug, Clone, Hash, PartialEq, Eq)]
+    enum GameState {
+        Menu,
+        Playing,
+    }
+
+    #[derive(Unique, Default)]
+    struct StateLog(Vec<&'static str>);
+
+    // Systems are told apart by type, so each entry needs its own function
+    macro_rules! log_systems {
+        ($($name:ident => $entry:literal),* $(,)?) => {
+            $(fn $name(mut state_log: ResMut<StateLog>) {
+                state_log.0.push($entry);
+            })*
+        };
+    }
+
+    log_systems! {
+        sys_enter_menu => "enter menu",
+        sys_exit_menu => "exit menu",
+        sys_update_menu => "update menu",
+        sys_enter_playing => "enter playing",
+        sys_exit_playing => "exit playing",
+        sys_update_playing => "update playing",
+    }
+
+    fn build_state_app(builder: &mut WorkloadBuilder) {
+        builder
+            .insert(StateLog::default())
+            .register_state(GameState::Menu)
+            .add_state_workload(OnEnter(GameState::Menu), sys_enter_menu)
+            .add_state_workload(OnExit(GameState::Menu), sys_exit_menu)
+            .add_state_workload(OnUpdate(GameState::Menu), sys_update_menu)
+            .add_state_workload(OnEnter(GameState::Playing), sys_enter_playing)
+            .add_state_workload(OnExit(GameState::Playing), sys_exit_playing)
+            .add_state_workload(OnUpdate(GameState::Playing), sys_update_playing);
+    }
+
+    fn take_log(runner: &HeadlessRunner) -> Vec<&'static str> {
+        runner
+            .world()
+            .run(|mut state_log: ResMut<StateLog>| std::mem::take(&mut state_log.0))
+    }
+
+    fn set_state(runner: &HeadlessRunner, state: GameState) {
+        runner
+            .world()
+            .run(|mut app_state: ResMut<AppState<GameState>>| app_state.set(state));
+    }
+
+    #[test]
+    fn state_workloads_run_in_order() {
+        let mut runner = HeadlessRunner::new(build_state_app);
+
+        runner.step();
+        assert_eq!(take_log(&runner), vec!["enter menu", "update menu"]);
+
+        runner.step();
+        assert_eq!(take_log(&runner), vec!["update menu"]);
+
+        set_state(&runner, GameState::Playing);
+        runner.step();
+        assert_eq!(
+            take_log(&runner),
+            vec!["exit menu", "enter playing", "update playing"]
+        );
+    }
+
+    #[test]
+    fn states_queued_during_setup_are_applied() {
+        let mut runner = HeadlessRunner::new(|builder| {
+            build_state_app(builder);
+            builder.add_workload(Setup, |mut app_state: ResMut<AppState<GameState>>| {
+                app_state.set(GameState::Playing)
+            });
+        });
+
+        runner.step_frames(2);
+        assert_eq!(
+            take_log(&runner),
+            vec![
+                "enter menu",
+                "update menu",
+                "exit menu",
+                "enter playing",
+                "update playing"
+            ]
+        );
+    }
+
+    #[test]
+    fn queueing_the_current_state_does_nothing() {
+        let mut runner = HeadlessRunner::new(build_state_app);
+        runner.step();
+        take_log(&runner);
+
+        set_state(&runner, GameState::Menu);
+        runner.step();
+
+        assert_eq!(take_log(&runner), vec!["update menu"]);
+        runner.world().run(|app_state: Res<AppState<GameState>>| {
+            assert!(app_state.is(&GameState::Menu));
+            assert!(app_state.queued().is_none());
+        });
+    }
+
+    //--------------------------------------------------
+
+    #[derive(Component, Debug, Clone, Copy, PartialEq)]
+    struct Marker(u32);
+
+    #[derive(Component)]
+    struct Extra;
+
+    #[derive(Unique)]
+    struct Targets {
+        insert: EntityId,
+        remove: EntityId,
+        despawn: EntityId,
+        sent: bool,
+    }
+
+    #[derive(Unique)]
+    struct Flag;
+
+    #[derive(Unique, Default, Debug, PartialEq)]
+    struct Seen {
+        markers: Vec<u32>,
+        extras: usize,
+        despawned: bool,
+        flag: bool,
+    }
+
+    fn sys_send_commands(mut targets: ResMut<Targets>, mut commands: Commands) {
+        if std::mem::replace(&mut targets.sent, true) {
+            return;
+        }
+
+        commands.spawn((Marker(3),));
+        commands
+            .insert(targets.insert, (Marker(2),))
+            .remove::<(Extra,)>(targets.remove)
+            .despawn(targets.despawn)
+            .insert_unique(Flag);
+    }
+
+    fn sys_observe(
+        v_marker: View<Marker>,
+        v_extra: View<Extra>,
+        entities: EntitiesView,
+        targets: Res<Targets>,
+        flag: Option<Res<Flag>>,
+        mut seen: ResMut<Seen>,
+    ) {
+        let mut markers = v_marker.iter().map(|marker| marker.0).collect::<Vec<_>>();
+        markers.sort();
+
+        *seen = Seen {
+            markers,
+            extras: v_extra.iter().count(),
+            despawned: !entities.is_alive(targets.despawn),
+            flag: flag.is_some(),
+        };
+    }
+
+    #[test]
+    fn commands_apply_before_the_next_substage() {
+        let mut runner = HeadlessRunner::new(|builder| {
+            builder
+                .insert(Seen::default())
+                .add_workload_pre(Update, sys_send_commands)
+                .add_workload(Update, sys_observe);
+        });
+
+        runner.world().run(|mut all_storages: AllStoragesViewMut| {
+            let targets = Targets {
+                insert: all_storages.add_entity((Marker(1),)),
+                remove: all_storages.add_entity((Extra,)),
+                despawn: all_storages.add_entity((Marker(4),)),
+                sent: false,
+            };
+            all_storages.add_unique(targets);
+        });
+
+        runner.step();
+
+        let seen = runner
+            .world()
+            .run(|mut seen: ResMut<Seen>| std::mem::take(&mut *seen));
+        assert_eq!(
+            seen,
+            Seen {
+                markers: vec![2, 3],
+                extras: 0,
+                despawned: true,
+                flag: true,
+            }
+        );
+    }
+
+    #[derive(Unique, Default)]
+    struct Applied(Vec<&'static str>);
+
+    fn sys_ordered_commands(mut commands: Commands) {
+        commands.add(|all_storages| {
+            all_storages.add_unique(Applied(vec!["first"]));
+        });
+
+        commands
+            .spawn((Marker(1),))
+            .remove::<(Marker,)>()
+            .insert((Marker(2),))
+            .add(|entity, all_storages| {
+                let marker = *all_storages
+                    .borrow::<View<Marker>>()
+                    .unwrap()
+                    .get(entity)
+                    .unwrap();
+                assert_eq!(marker, Marker(2));
+                all_storages
+                    .borrow::<ResMut<Applied>>()
+                    .unwrap()
+                    .0
+                    .push("entity");
+            });
+
+        commands.add(|all_storages| {
+            all_storages
+                .borrow::<ResMut<Applied>>()
+                .unwrap()
+                .0
+                .push("last");
+        });
+    }
+
+    #[test]
+    fn commands_from_a_system_apply_in_order() {
+        let mut runner = HeadlessRunner::new(|builder| {
+            builder.add_workload(Setup, sys_ordered_commands);
+        });
+        runner.step();
+
+        runner
+            .world()
+            .run(|applied: Res<Applied>, v_marker: View<Marker>| {
+                assert_eq!(applied.0, vec!["first", "entity", "last"]);
+                assert_eq!(
+                    v_marker.iter().copied().collect::<Vec<_>>(),
+                    vec![Marker(2)]
+                );
+            });
+    }
+
+    //--------------------------------------------------
+
+    #[derive(Unique)]
+    struct NeverInserted;
+
+    #[derive(Unique, Default)]
+    struct LastRuns(u32);
+
+    fn sys_needs_missing(_: Res<NeverInserted>) {}
+
+    fn sys_count_last(mut runs: ResMut<LastRuns>) {
+        runs.0 += 1;
+    }
+
+    fn error_app(policy: ErrorPolicy) -> HeadlessRunner {
+        HeadlessRunner::new(move |builder| {
+            builder
+                .set_error_policy(policy)
+                .insert(LastRuns::default())
+                .add_workload(Update, sys_needs_missing)
+                .add_workload(Last, sys_count_last);
+        })
+    }
+
+    #[test]
+    fn stop_policy_stops_on_missing_uniques() {
+        let mut runner = error_app(ErrorPolicy::Stop);
+        runner.step_frames(3);
+
+        let error = runner.stopped().unwrap();
+        assert!(error.missing_storage().unwrap().contains("NeverInserted"));
+        assert!(error
+            .failing_system()
+            .unwrap()
+            .contains("sys_needs_missing"));
+
+        // Nothing after the failing stage ran
+        assert_eq!(runner.frame(), 0);
+        assert_eq!(runner.world().run(|runs: Res<LastRuns>| runs.0), 0);
+    }
+
+    #[test]
+    fn log_and_skip_policy_keeps_stepping() {
+        let mut runner = error_app(ErrorPolicy::LogAndSkip);
+        runner.step_frames(3);
+
+        assert!(runner.stopped().is_none());
+        assert_eq!(runner.frame(), 3);
+        assert_eq!(runner.world().run(|runs: Res<LastRuns>| runs.0), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "sys_needs_missing")]
+    fn panic_policy_panics() {
+        error_app(ErrorPolicy::Panic).step();
+    }
+}
@@ -1,24 +1,27 @@
 //====================================================================
 
-use std::{sync::Arc, time::Duration};
+use std::sync::Arc;
 
 use events::WindowInputEvent;
-use feathered_common::Size;
+use feathered_common::{Duration, Instant, Size, WindowDescriptor};
 use feathered_shipyard::{
     builder::{register_main_stages, WorkloadBuilder},
-    events::EventBuilder,
+    error::FeatheredError,
+    events::{Event, EventBuilder, EventReader, ReadEvents},
     runner::WorkloadRunner,
     tools::UniqueTools,
+    Res, ResMut,
 };
-use shipyard::Unique;
+use shipyard::{EntityId, Get, Unique, View};
 use winit::{
     application::ApplicationHandler,
     event::{DeviceEvent, DeviceId, StartCause, WindowEvent},
-    event_loop::{ActiveEventLoop, EventLoop},
-    window::{WindowAttributes, WindowId},
+    event_loop::{ActiveEventLoop, ControlFlow, EventLoop},
+    window::WindowId,
 };
 
 pub mod events;
+pub mod headless;
 pub mod window;
 
 //====================================================================
@@ -36,13 +39,13 @@ impl Runner {
         F: FnOnce(&mut WorkloadBuilder),
     {
         let world = shipyard::World::new();
-        let mut builder = WorkloadBuilder::new(&world);
-
-        register_main_stages(&mut builder);
-        builder.register_event::<WindowInputEvent>();
-
-        build_app(&mut builder);
-        let runner = builder.build();
+        let runner = match build_workloads(&world, build_app) {
+            Ok(runner) => runner,
+            Err(e) => {
+                log::error!("Unable to start app - {}", e);
+                return;
+            }
+        };
 
         let mut runner = Self(RunnerInnerState::Waiting(Some((world, runner))));
 
@@ -57,14 +60,20 @@ impl ApplicationHandler for Runner {
 
         let (world, workload_runner) = match &mut self.0 {
             RunnerInnerState::Waiting(inner) => inner.take().unwrap(),
-            RunnerInnerState::Running(..) => {
-                log::warn!("Application resumed again...");
+            RunnerInnerState::Running(inner) => {
+                log::trace!("App Resumed");
+                inner.send_event(events::AppResumed);
                 return;
             }
         };
 
-        let inner = RunnerInner::new(event_loop, world, workload_runner);
-        self.0 = RunnerInnerState::Running(inner);
+        match RunnerInner::new(event_loop, world, workload_runner) {
+            Ok(inner) => self.0 = RunnerInnerState::Running(inner),
+            Err(e) => {
+                log::error!("Unable to start app - {}", e);
+                event_loop.exit();
+            }
+        }
     }
 
     fn window_event(
@@ -106,38 +115,135 @@ impl ApplicationHandler for Runner {
         let _ = event_loop;
     }
 
-    fn suspended(&mut self, event_loop: &ActiveEventLoop) {
-        let _ = event_loop;
+    fn suspended(&mut self, _event_loop: &ActiveEventLoop) {
+        if let RunnerInnerState::Running(inner) = &mut self.0 {
+            log::trace!("App Suspended");
+            inner.send_event(events::AppSuspended);
+        }
     }
 
-    fn exiting(&mut self, event_loop: &ActiveEventLoop) {
-        let _ = event_loop;
+    fn exiting(&mut self, _event_loop: &ActiveEventLoop) {
+        log::info!("Exiting App");
     }
 
-    fn memory_warning(&mut self, event_loop: &ActiveEventLoop) {
-        let _ = event_loop;
+    fn memory_warning(&mut self, _event_loop: &ActiveEventLoop) {
+        if let RunnerInnerState::Running(inner) = &mut self.0 {
+            log::warn!("Memory warning received");
+            inner.send_event(events::MemoryWarning);
+        }
     }
 }
 
+pub(crate) fn build_workloads<F>(
+    world: &shipyard::World,
+    build_app: F,
+) -> Result<WorkloadRunner, FeatheredError>
+where
+    F: FnOnce(&mut WorkloadBuilder),
+{
+    let mut builder = WorkloadBuilder::new(world);
+
+    register_main_stages(&mut builder);
+    builder
+        .insert(window::Windows::default())
+        .insert(window::WindowRequests::default())
+        .register_event::<WindowInputEvent>()
+        .register_event::<window::WindowOpened>()
+        .register_event::<window::WindowClosed>()
+        .register_event::<window::WindowCommandResult>()
+        .insert(events::CloseRequests::default())
+        .register_event::<events::WindowFocused>()
+        .register_event::<events::AppSuspended>()
+        .register_event::<events::AppResumed>()
+        .register_event::<events::MemoryWarning>()
+        .register_event::<events::CloseRequested>()
+        .register_event::<events::AppExit>();
+
+    build_app(&mut builder);
+    builder.build()
+}
+
 //====================================================================
 
-const DEFAULT_TIMESTEP: f32 = 1. / 75.;
+const DEFAULT_TARGET_FPS: f32 = 75.;
 
-#[derive(Unique)]
-pub struct RunnerTargetFPS(Duration);
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PacingMode {
+    // Start the next frame as soon as the last one finishes
+    Continuous,
+    // Frames start on fixed deadlines, so time spent on a frame doesn't lower the rate
+    TargetFps(f32),
+    // Only redraw after window events, input or `FramePacing::request_redraw`
+    Reactive,
+}
 
-impl RunnerTargetFPS {
-    pub fn update_target(&mut self, duration: Duration) {
-        // if duration.as_secs_f32() >= 1. {
-        //     return;
-        // }
-        self.0 = duration;
-    }
+#[derive(Unique, Debug)]
+pub struct FramePacing {
+    mode: PacingMode,
+    next_frame: Option<Instant>,
+    redraw_requested: bool,
 }
 
-impl Default for RunnerTargetFPS {
+impl Default for FramePacing {
     fn default() -> Self {
-        Self(Duration::from_secs_f32(DEFAULT_TIMESTEP))
+        Self::new(PacingMode::TargetFps(DEFAULT_TARGET_FPS))
+    }
+}
+
+impl FramePacing {
+    pub fn new(mode: PacingMode) -> Self {
+        let mut pacing = Self {
+            mode: PacingMode::Continuous,
+            next_frame: None,
+            redraw_requested: false,
+        };
+        pacing.set_mode(mode);
+        pacing
+    }
+
+    #[inline]
+    pub fn mode(&self) -> PacingMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: PacingMode) {
+        if let PacingMode::TargetFps(fps) = mode {
+            if !fps.is_finite() || fps <= 0. {
+                log::warn!("Invalid target fps '{}' provided", fps);
+                return;
+            }
+        }
+
+        self.mode = mode;
+        self.next_frame = None;
+    }
+
+    // Run another frame after this one. Only needed with `PacingMode::Reactive`.
+    #[inline]
+    pub fn request_redraw(&mut self) {
+        self.redraw_requested = true;
+    }
+
+    // Returns the control flow to wait with and whether to redraw straight away
+    fn end_frame(&mut self, now: Instant) -> (ControlFlow, bool) {
+        let redraw_requested = std::mem::take(&mut self.redraw_requested);
+
+        match self.mode {
+            PacingMode::Continuous => (ControlFlow::Poll, true),
+
+            PacingMode::TargetFps(fps) => {
+                let frame_time = Duration::from_secs_f32(1. / fps);
+
+                // Deadlines follow on from the last one rather than when the frame ended.
+                // If a frame overran, start the next straight away and pace from there.
+                let next_frame = (self.next_frame.unwrap_or(now) + frame_time).max(now);
+                self.next_frame = Some(next_frame);
+
+                (ControlFlow::WaitUntil(next_frame), false)
+            }
+
+            PacingMode::Reactive => (ControlFlow::Wait, redraw_requested),
+        }
     }
 }
 
@@ -152,100 +258,215 @@ impl RunnerInner {
     fn new(
         event_loop: &ActiveEventLoop,
         world: shipyard::World,
-        workload_runner: WorkloadRunner,
-    ) -> Self {
-        let window = Arc::new(
-            event_loop
-                .create_window(WindowAttributes::default())
-                .unwrap(),
-        );
+        mut workload_runner: WorkloadRunner,
+    ) -> Result<Self, FeatheredError> {
+        if world.borrow::<Res<WindowDescriptor>>().is_err() {
+            world.insert(WindowDescriptor::default());
+        }
+
+        let attributes = world.run(|descriptor: Res<WindowDescriptor>| {
+            window::window_attributes(&descriptor, event_loop)
+        });
 
-        world.run_with_data(window::sys_add_window, window);
-        world.insert(RunnerTargetFPS::default());
-        workload_runner.prep(&world);
+        let window = Arc::new(event_loop.create_window(attributes).unwrap());
 
-        Self {
+        world.run_with_data(window::sys_add_window, (window, true));
+        if world.borrow::<Res<FramePacing>>().is_err() {
+            world.insert(FramePacing::default());
+        }
+        workload_runner.prep(&world)?;
+
+        Ok(Self {
             world,
             workload_runner,
-        }
+        })
     }
 
     // TODO
     fn window_event(
         &mut self,
         event_loop: &ActiveEventLoop,
-        _window_id: WindowId,
+        window_id: WindowId,
         event: WindowEvent,
     ) {
+        let Some(window) = self
+            .world
+            .run(|windows: Res<window::Windows>| windows.get(window_id))
+        else {
+            return;
+        };
+
+        if !matches!(event, WindowEvent::RedrawRequested) {
+            self.reactive_redraw();
+        }
+
         match event {
             WindowEvent::Resized(new_size) => {
-                self.resize(Size::new(new_size.width, new_size.height))
+                self.resize(window, Size::new(new_size.width, new_size.height))
             }
 
             WindowEvent::Destroyed => log::error!("Window was destroyed"), // panic!("Window was destroyed"),
+            // Handled after the next frame, giving systems a chance to veto it
             WindowEvent::CloseRequested => {
-                log::info!("Close requested. Closing App.");
-                event_loop.exit();
+                log::info!("Close requested for window {:?}", window);
+
+                self.world
+                    .run(|mut requests: ResMut<events::CloseRequests>| requests.request(window));
+                self.send_event(events::CloseRequested { window });
+            }
+
+            WindowEvent::Focused(focused) => {
+                self.send_event(events::WindowFocused { window, focused })
             }
 
             WindowEvent::RedrawRequested => {
-                self.tick();
+                // Other windows are drawn as part of the primary window's frame
+                if !self.is_primary(window) {
+                    return;
+                }
+
+                if let Err(e) = self.tick() {
+                    log::error!("Closing App - {}", e);
+                    event_loop.exit();
+                    return;
+                }
+
+                self.process_window_requests(event_loop);
 
-                let timestep = self
+                if self.process_exit() {
+                    event_loop.exit();
+                    return;
+                }
+
+                let (control_flow, redraw) = self
                     .world
-                    .borrow::<feathered_shipyard::Res<RunnerTargetFPS>>()
-                    .unwrap();
+                    .run(|mut pacing: ResMut<FramePacing>| pacing.end_frame(Instant::now()));
+
+                event_loop.set_control_flow(control_flow);
 
-                event_loop
-                    .set_control_flow(winit::event_loop::ControlFlow::wait_duration(timestep.0));
+                if redraw {
+                    self.request_redraw();
+                }
             }
 
             WindowEvent::KeyboardInput { event, .. } => {
+                let pressed = event.state.is_pressed();
+
                 if let winit::keyboard::PhysicalKey::Code(key) = event.physical_key {
-                    self.world.run_with_data(
-                        events::sys_send_event,
-                        WindowInputEvent::KeyInput {
-                            key,
-                            pressed: event.state.is_pressed(),
-                        },
-                    )
+                    self.send_event(WindowInputEvent::KeyInput {
+                        window,
+                        key,
+                        logical_key: event.logical_key.clone(),
+                        pressed,
+                        repeat: event.repeat,
+                    });
+                }
+
+                if let (true, Some(text)) = (pressed, event.text) {
+                    self.send_event(WindowInputEvent::Text {
+                        window,
+                        text: text.to_string(),
+                    });
                 }
             }
 
-            WindowEvent::MouseInput { state, button, .. } => self.world.run_with_data(
-                events::sys_send_event,
-                WindowInputEvent::MouseInput {
+            WindowEvent::Ime(ime) => self.send_event(WindowInputEvent::Ime { window, ime }),
+
+            WindowEvent::ModifiersChanged(modifiers) => {
+                self.send_event(WindowInputEvent::ModifiersChanged {
+                    window,
+                    modifiers: modifiers.state(),
+                })
+            }
+
+            WindowEvent::MouseInput { state, button, .. } => {
+                self.send_event(WindowInputEvent::MouseInput {
+                    window,
                     button,
                     pressed: state.is_pressed(),
-                },
-            ),
+                })
+            }
 
-            WindowEvent::CursorMoved { position, .. } => self.world.run_with_data(
-                events::sys_send_event,
-                WindowInputEvent::CursorMoved {
+            WindowEvent::CursorMoved { position, .. } => {
+                self.world
+                    .run_with_data(window::sys_update_cursor, (window, Some(position.into())));
+
+                self.send_event(WindowInputEvent::CursorMoved {
+                    window,
                     position: position.into(),
-                },
-            ),
+                })
+            }
+
+            WindowEvent::CursorEntered { .. } => {
+                self.send_event(WindowInputEvent::CursorEntered { window })
+            }
+
+            WindowEvent::CursorLeft { .. } => {
+                self.world
+                    .run_with_data(window::sys_update_cursor, (window, None));
+
+                self.send_event(WindowInputEvent::CursorLeft { window })
+            }
 
             WindowEvent::MouseWheel { delta, .. } => match delta {
                 winit::event::MouseScrollDelta::LineDelta(h, v) => {
-                    self.world.run_with_data(
-                        events::sys_send_event,
-                        WindowInputEvent::MouseWheel { delta: (h, v) },
-                    );
+                    self.send_event(WindowInputEvent::MouseWheel {
+                        window,
+                        delta: (h, v),
+                    });
+                }
+                winit::event::MouseScrollDelta::PixelDelta(delta) => {
+                    self.send_event(WindowInputEvent::MouseWheelPixels {
+                        window,
+                        delta: delta.into(),
+                    });
                 }
-                winit::event::MouseScrollDelta::PixelDelta(_) => {}
             },
 
+            WindowEvent::Touch(touch) => self.send_event(WindowInputEvent::Touch {
+                window,
+                id: touch.id,
+                phase: touch.phase,
+                position: touch.location.into(),
+            }),
+
+            WindowEvent::HoveredFile(path) => {
+                self.send_event(WindowInputEvent::FileHovered { window, path })
+            }
+
+            WindowEvent::HoveredFileCancelled => {
+                self.send_event(WindowInputEvent::FileHoverCancelled { window })
+            }
+
+            WindowEvent::DroppedFile(path) => {
+                self.send_event(WindowInputEvent::FileDropped { window, path })
+            }
+
             _ => {}
         }
     }
 
     fn resumed(&mut self) {
+        self.request_redraw();
+    }
+
+    #[inline]
+    fn request_redraw(&self) {
         self.world
             .run(|window: shipyard::UniqueView<window::Window>| window.inner().request_redraw());
     }
 
+    // Reactive pacing redraws after any window or device event
+    fn reactive_redraw(&self) {
+        let reactive = self
+            .world
+            .run(|pacing: Res<FramePacing>| pacing.mode() == PacingMode::Reactive);
+
+        if reactive {
+            self.request_redraw();
+        }
+    }
+
     // TODO
     fn device_event(
         &mut self,
@@ -253,29 +474,207 @@ impl RunnerInner {
         _device_id: DeviceId,
         event: DeviceEvent,
     ) {
-        match event {
-            DeviceEvent::MouseMotion { delta } => self.world.run_with_data(
+        self.reactive_redraw();
+
+        if let DeviceEvent::MouseMotion { delta } = event {
+            self.world.run_with_data(
                 events::sys_send_event,
                 WindowInputEvent::CursorMotion { delta },
-            ),
-            _ => {}
+            )
         }
     }
 }
 
 impl RunnerInner {
-    fn resize(&mut self, new_size: Size<u32>) {
+    fn resize(&mut self, window: EntityId, new_size: Size<u32>) {
         if new_size.width == 0 || new_size.height == 0 {
             log::warn!("Resize width or height of '0' provided");
             return;
         }
 
-        self.world.run_with_data(window::sys_resize, new_size);
+        self.world
+            .run_with_data(window::sys_resize, (window, new_size));
+    }
+
+    #[inline]
+    fn is_primary(&self, window: EntityId) -> bool {
+        self.world
+            .run(|windows: Res<window::Windows>| windows.is_primary(window))
     }
 
-    fn tick(&mut self) {
-        self.workload_runner.run(&self.world);
+    fn close_window(&mut self, window: EntityId) {
+        if self.world.run_with_data(window::sys_remove_window, window) {
+            self.world
+                .run_with_data(events::sys_send_event, window::WindowClosed { window });
+        }
+    }
+
+    // Open and close any windows requested during the last frame
+    fn process_window_requests(&mut self, event_loop: &ActiveEventLoop) {
+        let requests = self
+            .world
+            .run(|mut requests: ResMut<window::WindowRequests>| requests.take());
+
+        requests.open.into_iter().for_each(|(request, attributes)| {
+            let new_window = match event_loop.create_window(attributes) {
+                Ok(new_window) => Arc::new(new_window),
+                Err(e) => {
+                    log::error!("Unable to open window - {}", e);
+                    return;
+                }
+            };
+
+            let window = self
+                .world
+                .run_with_data(window::sys_add_window, (new_window, false));
+
+            self.world.run_with_data(
+                events::sys_send_event,
+                window::WindowOpened { window, request },
+            );
+        });
+
+        let results = {
+            let v_window = self.world.borrow::<View<window::Window>>().unwrap();
+
+            requests
+                .commands
+                .into_iter()
+                .map(|(window, command)| {
+                    let (command, result) = match v_window.get(window) {
+                        Ok(inner) => window::apply_command(inner, command, event_loop),
+                        Err(_) => (command, Err(window::WindowCommandError::MissingWindow)),
+                    };
+
+                    if let Err(e) = &result {
+                        log::warn!("Window command {:?} failed - {}", command, e);
+                    }
+
+                    window::WindowCommandResult {
+                        window,
+                        command,
+                        result,
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+
+        results.into_iter().for_each(|result| {
+            self.world.run_with_data(events::sys_send_event, result);
+        });
+
+        requests.close.into_iter().for_each(|window| {
+            if self.is_primary(window) {
+                log::warn!("The primary window can't be closed through window requests");
+                return;
+            }
+
+            self.close_window(window);
+        });
+    }
+
+    // Closes any windows that weren't vetoed. Returns true if the app should exit.
+    fn process_exit(&mut self) -> bool {
+        let closing = self
+            .world
+            .run(|mut requests: ResMut<events::CloseRequests>| requests.take());
+
+        for window in closing {
+            if self.is_primary(window) {
+                log::info!("Primary window closed. Closing App.");
+                return true;
+            }
+
+            log::info!("Closing window {:?}", window);
+            self.close_window(window);
+        }
+
+        let exit = self
+            .world
+            .run(|exit: EventReader<events::AppExit>| !exit.is_empty());
+
+        if exit {
+            log::info!("Exit requested. Closing App.");
+        }
+
+        exit
+    }
+
+    #[inline]
+    fn send_event<E: Event>(&self, event: E) {
+        self.world.run_with_data(events::sys_send_event, event);
+    }
+
+    #[inline]
+    fn tick(&mut self) -> Result<(), FeatheredError> {
+        self.workload_runner.run(&self.world)
     }
 }
 
 //====================================================================
+
+#[cfg(test)]
+mod tests {
+    use feathered_common::{Duration, Instant};
+    use winit::event_loop::ControlFlow;
+
+    use super::{FramePacing, PacingMode};
+
+    #[test]
+    fn target_fps_keeps_steady_deadlines() {
+        let mut pacing = FramePacing::new(PacingMode::TargetFps(50.));
+        let frame = Duration::from_secs_f32(1. / 50.);
+        let start = Instant::now();
+
+        assert_eq!(
+            pacing.end_frame(start),
+            (ControlFlow::WaitUntil(start + frame), false)
+        );
+
+        // Follows on from the last deadline, however long the frame took
+        assert_eq!(
+            pacing.end_frame(start + Duration::from_millis(5)),
+            (ControlFlow::WaitUntil(start + frame * 2), false)
+        );
+        assert_eq!(
+            pacing.end_frame(start + Duration::from_millis(27)),
+            (ControlFlow::WaitUntil(start + frame * 3), false)
+        );
+    }
+
+    #[test]
+    fn target_fps_restarts_after_an_overrun() {
+        let mut pacing = FramePacing::new(PacingMode::TargetFps(50.));
+        let frame = Duration::from_secs_f32(1. / 50.);
+        let start = Instant::now();
+
+        pacing.end_frame(start);
+
+        // The missed deadline isn't caught up on
+        let late = start + Duration::from_millis(100);
+        assert_eq!(
+            pacing.end_frame(late),
+            (ControlFlow::WaitUntil(late), false)
+        );
+        assert_eq!(
+            pacing.end_frame(late + Duration::from_millis(1)),
+            (ControlFlow::WaitUntil(late + frame), false)
+        );
+    }
+
+    #[test]
+    fn reactive_only_redraws_when_requested() {
+        let mut pacing = FramePacing::new(PacingMode::Reactive);
+        let now = Instant::now();
+
+        assert_eq!(pacing.end_frame(now), (ControlFlow::Wait, false));
+
+        pacing.request_redraw();
+        assert_eq!(pacing.end_frame(now), (ControlFlow::Wait, true));
+        assert_eq!(pacing.end_frame(now), (ControlFlow::Wait, false));
+
+        // Requests are ignored by the other modes
+        pacing.set_mode(PacingMode::Continuous);
+        assert_eq!(pacing.end_frame(now), (ControlFlow::Poll, true));
+    }
+}
@@ -1,18 +1,28 @@
 //====================================================================
 
-use std::sync::Arc;
+use std::{collections::HashMap, error::Error, fmt::Display, sync::Arc};
 
-use feathered_common::{Size, WindowRaw, WindowResizeEvent, WindowSize};
+pub use winit::window::CursorIcon;
+
+use feathered_common::{
+    PrimaryWindow, Size, WindowDescriptor, WindowMode, WindowRaw, WindowResizeEvent, WindowSize,
+};
 use feathered_shipyard::{
-    events::{EventSender, WriteEvents},
-    tools::UniqueTools,
-    ResMut,
+    events::{Event, EventSender, WriteEvents},
+    Res, ResMut,
+};
+use shipyard::{AllStoragesViewMut, Component, EntityId, Get, Unique, ViewMut};
+use winit::{
+    dpi::PhysicalSize,
+    error::ExternalError,
+    event_loop::ActiveEventLoop,
+    window::{CursorGrabMode, Fullscreen, Icon, WindowAttributes, WindowId},
 };
-use shipyard::{AllStoragesView, Unique};
 
 //====================================================================
 
-#[derive(Unique)]
+// The unique is the primary window. Every window is also an entity with this as a component.
+#[derive(Unique, Component)]
 pub struct Window(Arc<winit::window::Window>);
 
 impl Window {
@@ -21,6 +31,11 @@ impl Window {
         &self.0
     }
 
+    #[inline]
+    pub fn id(&self) -> WindowId {
+        self.0.id()
+    }
+
     #[inline]
     pub fn size(&self) -> Size<u32> {
         let window_size = self.0.inner_size();
@@ -35,12 +50,37 @@ impl Window {
     pub fn confine_cursor(&self, confined: bool) {
         log::trace!("Confining window cursor: {}", confined);
 
-        self.0
-            .set_cursor_grab(match confined {
-                true => winit::window::CursorGrabMode::Confined,
-                false => winit::window::CursorGrabMode::None,
-            })
-            .unwrap();
+        let grab = match confined {
+            true => CursorGrab::Confined,
+            false => CursorGrab::None,
+        };
+
+        if let Err(e) = self.set_cursor_grab(grab) {
+            log::warn!("Unable to confine window cursor - {}", e);
+        }
+    }
+
+    // Platforms tend to only support one of 'Confined' or 'Locked', so the other is tried if
+    // the requested mode fails. Returns the mode that was applied.
+    pub fn set_cursor_grab(&self, grab: CursorGrab) -> Result<CursorGrab, ExternalError> {
+        let Err(e) = self.0.set_cursor_grab(grab.into()) else {
+            return Ok(grab);
+        };
+
+        let fallback = match grab {
+            CursorGrab::None => return Err(e),
+            CursorGrab::Confined => CursorGrab::Locked,
+            CursorGrab::Locked => CursorGrab::Confined,
+        };
+
+        log::debug!(
+            "Cursor grab {:?} failed ({}). Trying {:?}.",
+            grab,
+            e,
+            fallback
+        );
+
+        self.0.set_cursor_grab(fallback.into()).map(|_| fallback)
     }
 
     #[inline]
@@ -50,23 +90,317 @@ impl Window {
     }
 }
 
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq)]
+pub struct WindowCursor {
+    // None while the cursor is outside of the window
+    pub position: Option<(f64, f64)>,
+}
+
+//--------------------------------------------------
+
+// Lookup from winit window ids to window entities
+#[derive(Unique, Debug, Default)]
+pub struct Windows {
+    entities: HashMap<WindowId, EntityId>,
+    primary: Option<EntityId>,
+}
+
+impl Windows {
+    #[inline]
+    pub fn get(&self, id: WindowId) -> Option<EntityId> {
+        self.entities.get(&id).copied()
+    }
+
+    #[inline]
+    pub fn primary(&self) -> Option<EntityId> {
+        self.primary
+    }
+
+    #[inline]
+    pub fn is_primary(&self, window: EntityId) -> bool {
+        self.primary == Some(window)
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (WindowId, EntityId)> + '_ {
+        self.entities.iter().map(|(id, entity)| (*id, *entity))
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+}
+
+//--------------------------------------------------
+
+// Windows can only be created by the event loop, so requests are queued and handled after
+// the frame. Results are sent as `WindowOpened`, `WindowClosed` and `WindowCommandResult` events.
+#[derive(Unique, Default)]
+pub struct WindowRequests {
+    next_request: u32,
+    open: Vec<(u32, WindowAttributes)>,
+    close: Vec<EntityId>,
+    commands: Vec<(EntityId, WindowCommand)>,
+}
+
+#[derive(Default)]
+pub(crate) struct PendingWindowRequests {
+    pub open: Vec<(u32, WindowAttributes)>,
+    pub close: Vec<EntityId>,
+    pub commands: Vec<(EntityId, WindowCommand)>,
+}
+
+impl WindowRequests {
+    // Returns an id to match up with the `WindowOpened` event
+    pub fn open(&mut self, attributes: WindowAttributes) -> u32 {
+        let request = self.next_request;
+        self.next_request = self.next_request.wrapping_add(1);
+
+        self.open.push((request, attributes));
+        request
+    }
+
+    #[inline]
+    pub fn close(&mut self, window: EntityId) {
+        self.close.push(window);
+    }
+
+    #[inline]
+    pub fn command(&mut self, window: EntityId, command: WindowCommand) {
+        self.commands.push((window, command));
+    }
+
+    #[inline]
+    pub(crate) fn take(&mut self) -> PendingWindowRequests {
+        PendingWindowRequests {
+            open: std::mem::take(&mut self.open),
+            close: std::mem::take(&mut self.close),
+            commands: std::mem::take(&mut self.commands),
+        }
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowOpened {
+    pub window: EntityId,
+    pub request: u32,
+}
+
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowClosed {
+    pub window: EntityId,
+}
+
+//--------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum CursorGrab {
+    #[default]
+    None,
+    // Cursor can move but can't leave the window
+    Confined,
+    // Cursor can't move. Use `WindowInputEvent::CursorMotion` for movement.
+    Locked,
+}
+
+impl From<CursorGrab> for CursorGrabMode {
+    #[inline]
+    fn from(value: CursorGrab) -> Self {
+        match value {
+            CursorGrab::None => CursorGrabMode::None,
+            CursorGrab::Confined => CursorGrabMode::Confined,
+            CursorGrab::Locked => CursorGrabMode::Locked,
+        }
+    }
+}
+
+// Changes to an existing window. Queued through `WindowRequests::command`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WindowCommand {
+    SetTitle(String),
+    SetMode(WindowMode),
+    // Switches between windowed and borderless fullscreen
+    ToggleFullscreen,
+    SetDecorations(bool),
+    SetCursorIcon(CursorIcon),
+    SetCursorVisible(bool),
+    SetCursorGrab(CursorGrab),
+    SetImeAllowed(bool),
+    SetMinimized(bool),
+    SetMaximized(bool),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WindowCommandError {
+    MissingWindow,
+    NotSupported,
+    Os(String),
+}
+
+impl Error for WindowCommandError {}
+
+impl Display for WindowCommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WindowCommandError::MissingWindow => write!(f, "Window does not exist"),
+            WindowCommandError::NotSupported => write!(f, "Not supported on this platform"),
+            WindowCommandError::Os(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<ExternalError> for WindowCommandError {
+    #[inline]
+    fn from(value: ExternalError) -> Self {
+        match value {
+            ExternalError::NotSupported(_) => WindowCommandError::NotSupported,
+            e => WindowCommandError::Os(e.to_string()),
+        }
+    }
+}
+
+// Sent for every command once it has been applied. Cursor grabs that fell back to the
+// other grab mode hold the mode used in 'command'.
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct WindowCommandResult {
+    pub window: EntityId,
+    pub command: WindowCommand,
+    pub result: Result<(), WindowCommandError>,
+}
+
 //====================================================================
 
-pub(crate) fn sys_add_window(window: Arc<winit::window::Window>, all_storages: AllStoragesView) {
+// Canvas size used on web when the descriptor doesn't give one
+#[cfg(target_arch = "wasm32")]
+const DEFAULT_CANVAS_SIZE: Size<u32> = Size {
+    width: 450,
+    height: 400,
+};
+
+pub(crate) fn window_attributes(
+    descriptor: &WindowDescriptor,
+    event_loop: &ActiveEventLoop,
+) -> WindowAttributes {
+    let mut attributes = WindowAttributes::default()
+        .with_title(descriptor.title.clone())
+        .with_resizable(descriptor.resizable)
+        .with_decorations(descriptor.decorations)
+        .with_fullscreen(fullscreen(descriptor.mode, event_loop));
+
+    if let Some(size) = descriptor.size {
+        attributes = attributes.with_inner_size(PhysicalSize::new(size.width, size.height));
+    }
+
+    if let Some(size) = descriptor.min_size {
+        attributes = attributes.with_min_inner_size(PhysicalSize::new(size.width, size.height));
+    }
+
+    if let Some(size) = descriptor.max_size {
+        attributes = attributes.with_max_inner_size(PhysicalSize::new(size.width, size.height));
+    }
+
+    if let Some(icon) = &descriptor.icon {
+        match Icon::from_rgba(icon.rgba.clone(), icon.width, icon.height) {
+            Ok(icon) => attributes = attributes.with_window_icon(Some(icon)),
+            Err(e) => log::warn!("Invalid window icon - {}", e),
+        }
+    }
+
+    attributes
+}
+
+fn fullscreen(mode: WindowMode, event_loop: &ActiveEventLoop) -> Option<Fullscreen> {
+    match mode {
+        WindowMode::Windowed => None,
+        WindowMode::BorderlessFullscreen => Some(Fullscreen::Borderless(None)),
+        WindowMode::ExclusiveFullscreen => {
+            let video_mode = event_loop
+                .primary_monitor()
+                .or_else(|| event_loop.available_monitors().next())
+                .and_then(|monitor| {
+                    monitor.video_modes().max_by_key(|mode| {
+                        let size = mode.size();
+                        (size.width * size.height, mode.refresh_rate_millihertz())
+                    })
+                });
+
+            match video_mode {
+                Some(video_mode) => Some(Fullscreen::Exclusive(video_mode)),
+                None => {
+                    log::warn!(
+                        "No video modes available for exclusive fullscreen. Using borderless."
+                    );
+                    Some(Fullscreen::Borderless(None))
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn apply_command(
+    window: &Window,
+    command: WindowCommand,
+    event_loop: &ActiveEventLoop,
+) -> (WindowCommand, Result<(), WindowCommandError>) {
+    log::trace!("Applying window command {:?}", command);
+
+    let inner = window.inner();
+
+    match command {
+        WindowCommand::SetTitle(ref title) => inner.set_title(title),
+        WindowCommand::SetMode(mode) => inner.set_fullscreen(fullscreen(mode, event_loop)),
+        WindowCommand::ToggleFullscreen => inner.set_fullscreen(match inner.fullscreen() {
+            Some(_) => None,
+            None => Some(Fullscreen::Borderless(None)),
+        }),
+        WindowCommand::SetDecorations(decorations) => inner.set_decorations(decorations),
+        WindowCommand::SetCursorIcon(icon) => inner.set_cursor(icon),
+        WindowCommand::SetCursorVisible(visible) => inner.set_cursor_visible(visible),
+        WindowCommand::SetCursorGrab(grab) => {
+            return match window.set_cursor_grab(grab) {
+                Ok(applied) => (WindowCommand::SetCursorGrab(applied), Ok(())),
+                Err(e) => (command, Err(e.into())),
+            }
+        }
+        WindowCommand::SetImeAllowed(allowed) => inner.set_ime_allowed(allowed),
+        WindowCommand::SetMinimized(minimized) => inner.set_minimized(minimized),
+        WindowCommand::SetMaximized(maximized) => inner.set_maximized(maximized),
+    }
+
+    (command, Ok(()))
+}
+
+pub(crate) fn sys_add_window(
+    (window, primary): (Arc<winit::window::Window>, bool),
+    mut all_storages: AllStoragesViewMut,
+) -> EntityId {
     #[cfg(target_arch = "wasm32")]
-    {
-        use winit::{dpi::PhysicalSize, platform::web::WindowExtWebSys};
+    if primary {
+        use winit::platform::web::WindowExtWebSys;
 
         log::info!("Adding canvas to window");
 
-        if let None = window.request_inner_size(PhysicalSize::new(450, 400)) {
+        let descriptor = all_storages
+            .borrow::<Res<WindowDescriptor>>()
+            .map(|descriptor| (*descriptor).clone())
+            .unwrap_or_default();
+
+        let size = descriptor.size.unwrap_or(DEFAULT_CANVAS_SIZE);
+
+        if let None = window.request_inner_size(PhysicalSize::new(size.width, size.height)) {
             log::warn!("Wasm Resize Warning: Got none when requesting window inner size");
         }
 
         web_sys::window()
             .and_then(|win| win.document())
             .and_then(|doc| {
-                let dst = doc.get_element_by_id("feathered_app")?;
+                let dst = doc.get_element_by_id(&descriptor.canvas_id)?;
                 let canvas = web_sys::Element::from(window.canvas()?);
                 dst.append_child(&canvas).ok()?;
                 Some(())
@@ -76,19 +410,80 @@ pub(crate) fn sys_add_window(window: Arc<winit::window::Window>, all_storages: A
 
     let size = Size::new(window.inner_size().width, window.inner_size().height);
 
-    all_storages
-        .insert(WindowSize::new(size))
-        .insert(Window(window.clone()))
-        .insert(WindowRaw::new(window.clone(), size));
+    let entity = all_storages.add_entity((
+        Window(window.clone()),
+        WindowSize::new(size),
+        WindowRaw::new(window.clone(), size),
+        WindowCursor::default(),
+    ));
+
+    if primary {
+        all_storages.add_component(entity, (PrimaryWindow,));
+
+        all_storages.add_unique(WindowSize::new(size));
+        all_storages.add_unique(Window(window.clone()));
+        all_storages.add_unique(WindowRaw::new(window.clone(), size));
+    }
+
+    let mut windows = all_storages.borrow::<ResMut<Windows>>().unwrap();
+    windows.entities.insert(window.id(), entity);
+    if primary {
+        windows.primary = Some(entity);
+    }
+
+    entity
+}
+
+// Returns false if the window didn't exist
+pub(crate) fn sys_remove_window(window: EntityId, mut all_storages: AllStoragesViewMut) -> bool {
+    {
+        let mut windows = all_storages.borrow::<ResMut<Windows>>().unwrap();
+
+        let Some(id) = windows
+            .entities
+            .iter()
+            .find(|(_, entity)| **entity == window)
+            .map(|(id, _)| *id)
+        else {
+            return false;
+        };
+
+        windows.entities.remove(&id);
+        if windows.primary == Some(window) {
+            windows.primary = None;
+        }
+    }
+
+    all_storages.delete_entity(window);
+    true
 }
 
 pub(crate) fn sys_resize(
-    new_size: Size<u32>,
-    mut window_size: ResMut<WindowSize>,
+    (window, new_size): (EntityId, Size<u32>),
+    windows: Res<Windows>,
+    mut vm_size: ViewMut<WindowSize>,
+    mut primary_size: ResMut<WindowSize>,
     mut resize_event: EventSender<WindowResizeEvent>,
 ) {
-    *window_size = WindowSize::new(new_size);
-    resize_event.send_event(WindowResizeEvent::new(new_size));
+    if let Ok(mut size) = (&mut vm_size).get(window) {
+        *size = WindowSize::new(new_size);
+    }
+
+    let primary = windows.is_primary(window);
+    if primary {
+        *primary_size = WindowSize::new(new_size);
+    }
+
+    resize_event.send_event(WindowResizeEvent::new(window, new_size, primary));
+}
+
+pub(crate) fn sys_update_cursor(
+    (window, position): (EntityId, Option<(f64, f64)>),
+    mut vm_cursor: ViewMut<WindowCursor>,
+) {
+    if let Ok(mut cursor) = (&mut vm_cursor).get(window) {
+        cursor.position = position;
+    }
 }
 
 //====================================================================
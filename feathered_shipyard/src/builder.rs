@@ -4,11 +4,18 @@ use std::{collections::HashMap, fmt::Debug, hash::Hash};
 
 use shipyard::{info::TypeId, AsLabel, WorkloadModificator, World};
 
-use crate::runner::WorkloadRunner;
+use crate::{
+    commands::{self, CommandQueue},
+    error::{BuildError, ErrorPolicy, FeatheredError, WorkloadError},
+    runner::WorkloadRunner,
+    schedule::{ScheduleInfo, StageInfo},
+};
 
 //====================================================================
 
 pub type Label = Box<dyn shipyard::Label>;
+pub type RunCondition = Box<dyn Fn(&World) -> bool>;
+pub type OnStageInsert = Box<dyn FnOnce(&mut WorkloadBuilder)>;
 
 //====================================================================
 
@@ -16,7 +23,7 @@ pub trait Stage: shipyard::Label + Debug {}
 
 stage_macros::create_stage!(Setup);
 stage_macros::create_stage!(First);
-// stage_macros::create_stage!(FixedUpdate);
+stage_macros::create_stage!(FixedUpdate);
 stage_macros::create_stage!(Update);
 stage_macros::create_stage!(RenderPrep);
 stage_macros::create_stage!(Render);
@@ -26,13 +33,35 @@ pub fn register_main_stages(builder: &mut WorkloadBuilder) {
     builder
         .register_stage(Setup, StageData::from_priority(0), None)
         .register_stage(First, StageData::from_priority(10), None)
+        .register_stage(FixedUpdate, StageData::from_priority(15), None)
         .register_stage(Update, StageData::from_priority(20), None)
-        .register_stage(RenderPrep, StageData::from_priority(38), None)
+        .register_stage(RenderPrep, StageData::from_priority(25), None)
         .register_stage(Render, StageData::from_priority(30), None)
         .register_stage(Last, StageData::from_priority(40), None);
+
+    // Catch any mistakes in the priorities above
+    let (_, stage_order) =
+        crate::runner::resolve_stage_order(&builder.inner.stage_data()).unwrap_or_default();
+    let main_order = [
+        First.as_label(),
+        FixedUpdate.as_label(),
+        Update.as_label(),
+        RenderPrep.as_label(),
+        Render.as_label(),
+        Last.as_label(),
+    ];
+
+    debug_assert!(
+        stage_order
+            .iter()
+            .filter(|stage| main_order.contains(stage))
+            .eq(main_order.iter()),
+        "Main stages are out of order: {:?}",
+        stage_order
+    );
 }
 
-mod stage_macros {
+pub(crate) mod stage_macros {
     macro_rules! create_stage {
         (
             $stage_name: ident
@@ -70,20 +99,47 @@ impl Iterator for SubStages {
 
 //====================================================================
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum StageOrder {
+    // Stages with a priority of 0 are setup stages and only run once
+    Priority(u32),
+    // Placed directly before or after another stage, in the order they were registered
+    Before(Label),
+    After(Label),
+}
+
 pub struct StageData {
-    pub priority: u32,
-    pub run_condition: Option<Box<dyn Fn(&World) -> bool>>,
+    pub order: StageOrder,
+    pub run_condition: Option<RunCondition>,
+    // Stage is run repeatedly for as long as this returns true (possibly zero times)
+    pub loop_condition: Option<RunCondition>,
     pub disabled: bool,
 }
 
 impl StageData {
-    pub fn from_priority(priority: u32) -> Self {
+    pub fn new(order: StageOrder) -> Self {
         Self {
-            priority,
+            order,
             run_condition: None,
+            loop_condition: None,
             disabled: false,
         }
     }
+
+    #[inline]
+    pub fn from_priority(priority: u32) -> Self {
+        Self::new(StageOrder::Priority(priority))
+    }
+
+    #[inline]
+    pub fn before(stage: impl Stage) -> Self {
+        Self::new(StageOrder::Before(stage.as_label()))
+    }
+
+    #[inline]
+    pub fn after(stage: impl Stage) -> Self {
+        Self::new(StageOrder::After(stage.as_label()))
+    }
 }
 
 //====================================================================
@@ -95,10 +151,18 @@ pub struct WorkloadBuilder<'a> {
 
 pub struct WorkloadBuilderInner {
     stages: HashMap<Label, StageData>,
+    stage_registration: Vec<Label>,
+    // Problems found while registering stages, reported when building
+    stage_errors: Vec<String>,
     workloads: HashMap<Label, WorkloadToBuild>,
+    workload_conditions: HashMap<Label, Vec<(SubStages, String)>>,
 
     registered_workload_names: HashMap<String, String>, // Type ID : Workload Name
     registered_plugins: Vec<TypeId>,
+    blocked_plugins: HashMap<TypeId, BlockedPlugin>,
+    plugin_dependencies: Vec<(&'static str, PluginDependency)>, // Plugin Name : Dependency
+
+    error_policy: ErrorPolicy,
 
     pub build_tabs: u8,
 }
@@ -122,66 +186,162 @@ impl WorkloadToBuild {
 impl<'a> WorkloadBuilder<'a> {
     pub fn new(world: &'a World) -> Self {
         log::trace!("Setting up Workload Builder");
+        world.add_unique(CommandQueue::default());
+
         Self {
             world,
             inner: WorkloadBuilderInner {
                 stages: HashMap::default(),
+                stage_registration: Vec::new(),
+                stage_errors: Vec::new(),
                 workloads: HashMap::default(),
+                workload_conditions: HashMap::default(),
 
                 registered_workload_names: HashMap::new(),
                 registered_plugins: Vec::new(),
+                blocked_plugins: HashMap::new(),
+                plugin_dependencies: Vec::new(),
+
+                error_policy: ErrorPolicy::default(),
 
                 build_tabs: 0,
             },
         }
     }
 
-    pub fn build(mut self) -> WorkloadRunner {
-        self.inner.workloads.drain().for_each(|(_, mut to_build)| {
-            enum_iterator::all::<SubStages>()
-                .into_iter()
-                .fold(to_build.main, |acc, substage| {
-                    // Check and add substage if it exists
-                    if let Some(workload) = to_build.substages.remove(&substage) {
-                        let workload = workload.tag(substage);
-                        return acc.merge(
-                            substage.into_iter().fold(workload, |acc, substage_after| {
-                                acc.before_all(substage_after)
-                            }),
-                        );
-                    }
+    pub fn build(mut self) -> Result<WorkloadRunner, FeatheredError> {
+        let (stage_order, unplaced_stages) =
+            match crate::runner::resolve_stage_order(&self.inner.stage_data()) {
+                Ok(order) => (order, Vec::new()),
+                Err(unplaced) => (Default::default(), unplaced),
+            };
 
-                    acc
-                })
-                .add_to_world(self.world)
-                .unwrap();
-        });
-
-        // Print debug data
-        let data = self.world.workloads_info().0.iter().fold(
-            String::from("Building workloads. Registered Stages and functions:"),
-            |acc, (name, workload_info)| {
-                let name = match self.inner.registered_workload_names.get(name) {
-                    Some(event_name) => event_name,
-                    None => name,
-                };
+        let mut errors = BuildError {
+            missing_plugins: self.inner.check_plugin_dependencies(),
+            stages: std::mem::take(&mut self.inner.stage_errors)
+                .into_iter()
+                .chain(unplaced_stages)
+                .collect(),
+            workloads: Vec::new(),
+        };
 
-                let acc = format!("{}\n{}", acc, name);
+        let mut built_substages = HashMap::new();
 
-                workload_info
-                    .batch_info
-                    .iter()
-                    .fold(acc, |acc, batch_info| {
-                        batch_info
-                            .systems()
-                            .fold(acc, |acc, system| format!("{}\n    {}", acc, system.name))
+        self.inner
+            .workloads
+            .drain()
+            .for_each(|(label, mut to_build)| {
+                let mut substages = to_build.substages.keys().copied().collect::<Vec<_>>();
+                substages.sort_by_key(|substage| *substage as u8);
+                built_substages.insert(label.clone(), substages);
+
+                let result = enum_iterator::all::<SubStages>()
+                    .fold(to_build.main, |acc, substage| {
+                        // Check and add substage if it exists
+                        if let Some(workload) = to_build.substages.remove(&substage) {
+                            let workload = workload.tag(substage);
+
+                            // Apply any commands recorded by the substage before the next one runs
+                            let apply_commands =
+                                commands::apply_commands_workload(substage).after_all(substage);
+
+                            return acc
+                                .merge(
+                                    substage.into_iter().fold(workload, |acc, substage_after| {
+                                        acc.before_all(substage_after)
+                                    }),
+                                )
+                                .merge(
+                                    substage
+                                        .into_iter()
+                                        .fold(apply_commands, |acc, substage_after| {
+                                            acc.before_all(substage_after)
+                                        }),
+                                );
+                        }
+
+                        acc
                     })
-            },
+                    .add_to_world(self.world);
+
+                if let Err(error) = result {
+                    errors.workloads.push(WorkloadError {
+                        workload: format!("{:?}", label),
+                        error,
+                    });
+                }
+            });
+
+        if !errors.is_empty() {
+            errors
+                .missing_plugins
+                .iter()
+                .for_each(|missing| log::error!("{}", missing));
+            errors
+                .stages
+                .iter()
+                .for_each(|stage| log::error!("{}", stage));
+            errors
+                .workloads
+                .iter()
+                .for_each(|workload| log::error!("Failed to build {}", workload));
+
+            return Err(FeatheredError::Build(errors));
+        }
+
+        let (setup_order, stage_order) = stage_order;
+
+        let workloads_info = self.world.workloads_info().0;
+
+        let schedule = ScheduleInfo::new(
+            setup_order
+                .iter()
+                .chain(stage_order.iter())
+                .filter_map(|label| {
+                    let data = self.inner.stages.get(label)?;
+                    let key = format!("{:?}", label);
+
+                    let name = match self.inner.registered_workload_names.get(&key) {
+                        Some(name) => name.clone(),
+                        None => key.clone(),
+                    };
+
+                    Some(StageInfo::new(
+                        label.clone(),
+                        name,
+                        data,
+                        built_substages
+                            .get(label)
+                            .map(|substages| substages.as_slice())
+                            .unwrap_or_default(),
+                        self.inner
+                            .workload_conditions
+                            .get(label)
+                            .map(|conditions| conditions.as_slice())
+                            .unwrap_or_default(),
+                        workloads_info.get(&key),
+                    ))
+                })
+                .collect(),
         );
 
-        log::trace!("{data}");
+        log::trace!("Building workloads. Registered Stages and functions:\n{schedule}");
+
+        self.world.add_unique(schedule);
+
+        let stages = self
+            .inner
+            .stage_registration
+            .drain(..)
+            .filter_map(|label| {
+                let data = self.inner.stages.remove(&label)?;
+                Some((label, data))
+            })
+            .collect();
 
-        WorkloadRunner::new(self.inner.stages)
+        Ok(WorkloadRunner::from_order(stages, setup_order, stage_order)
+            .with_unique_requirements(self.inner.unique_requirements())
+            .with_error_policy(self.inner.error_policy))
     }
 }
 
@@ -195,7 +355,7 @@ impl<'a> WorkloadBuilder<'a> {
 
     #[inline]
     pub fn get_world(&mut self) -> &World {
-        &self.world
+        self.world
     }
 }
 
@@ -203,6 +363,58 @@ impl<'a> WorkloadBuilder<'a> {
 
 pub trait Plugin {
     fn build_plugin(self, builder: &mut WorkloadBuilder);
+
+    // Required plugins are checked once all workloads have been built. Required uniques
+    // are checked before the setup stages run.
+    fn dependencies(&self) -> Vec<PluginDependency> {
+        Vec::new()
+    }
+}
+
+pub struct PluginDependency {
+    name: &'static str,
+    kind: DependencyKind,
+}
+
+enum DependencyKind {
+    Plugin(TypeId),
+    Unique(fn(&World) -> bool),
+}
+
+impl PluginDependency {
+    #[inline]
+    pub fn plugin<T: Plugin + 'static>() -> Self {
+        Self {
+            name: std::any::type_name::<T>(),
+            kind: DependencyKind::Plugin(TypeId::of::<T>()),
+        }
+    }
+
+    #[inline]
+    pub fn unique<U: shipyard::Unique + Send + Sync>() -> Self {
+        Self {
+            name: std::any::type_name::<U>(),
+            kind: DependencyKind::Unique(|world| world.borrow::<crate::Res<U>>().is_ok()),
+        }
+    }
+
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+// Plugins that have been disabled or replaced in a plugin group are skipped if they
+// are added again later on (for example by another plugin).
+enum BlockedPlugin {
+    Disabled,
+    Replaced(TypeId, &'static str),
+}
+
+pub(crate) struct UniqueRequirement {
+    pub plugin: &'static str,
+    pub unique: &'static str,
+    pub check: fn(&World) -> bool,
 }
 
 impl<'a> WorkloadBuilder<'a> {
@@ -217,16 +429,280 @@ impl<'a> WorkloadBuilder<'a> {
             return self;
         }
 
+        match self.inner.blocked_plugins.get(&plugin_id) {
+            Some(BlockedPlugin::Disabled) => {
+                self.inner.log(format!(
+                    "Skipping disabled plugin '{}'",
+                    std::any::type_name::<T>()
+                ));
+                return self;
+            }
+            Some(BlockedPlugin::Replaced(_, replacement)) => {
+                let replacement = *replacement;
+                self.inner.log(format!(
+                    "Skipping plugin '{}' - replaced by '{}'",
+                    std::any::type_name::<T>(),
+                    replacement
+                ));
+                return self;
+            }
+            None => {}
+        }
+
         self.inner
             .log(format!("Adding plugin '{}'", std::any::type_name::<T>()));
         self.inner.build_tabs += 1;
 
+        let dependencies = plugin.dependencies();
+        self.inner.plugin_dependencies.extend(
+            dependencies
+                .into_iter()
+                .map(|dependency| (std::any::type_name::<T>(), dependency)),
+        );
+
         plugin.build_plugin(self);
 
         self.inner.registered_plugins.push(plugin_id);
         self.inner.build_tabs -= 1;
         self
     }
+
+    pub fn add_plugins<G: PluginGroup>(&mut self, group: G) -> &mut Self {
+        let group = group.build();
+
+        self.inner
+            .log(format!("Adding plugin group '{}'", group.name));
+        self.inner.build_tabs += 1;
+
+        // Block plugins first so members adding them internally also respect the group
+        group.plugins.iter().for_each(|entry| {
+            if !entry.enabled {
+                self.inner
+                    .blocked_plugins
+                    .insert(entry.id, BlockedPlugin::Disabled);
+            }
+
+            if let Some((replaced, _)) = entry.replaces {
+                self.inner
+                    .blocked_plugins
+                    .insert(replaced, BlockedPlugin::Replaced(entry.id, entry.name));
+            }
+        });
+
+        group
+            .plugins
+            .into_iter()
+            .filter(|entry| entry.enabled)
+            .for_each(|entry| (entry.add)(self));
+
+        self.inner.build_tabs -= 1;
+        self
+    }
+}
+
+impl WorkloadBuilderInner {
+    fn check_plugin_dependencies(&self) -> Vec<String> {
+        self.plugin_dependencies
+            .iter()
+            .filter_map(|(plugin, dependency)| {
+                let DependencyKind::Plugin(id) = &dependency.kind else {
+                    return None;
+                };
+
+                if self.registered_plugins.contains(id) {
+                    return None;
+                }
+
+                let reason = match self.blocked_plugins.get(id) {
+                    Some(BlockedPlugin::Disabled) => "has been disabled".to_string(),
+                    Some(BlockedPlugin::Replaced(replacement, _))
+                        if self.registered_plugins.contains(replacement) =>
+                    {
+                        return None
+                    }
+                    Some(BlockedPlugin::Replaced(_, replacement)) => {
+                        format!("was replaced by '{}' which was never added", replacement)
+                    }
+                    None => "was never added".to_string(),
+                };
+
+                Some(format!(
+                    "Plugin '{}' requires plugin '{}' which {}",
+                    plugin, dependency.name, reason
+                ))
+            })
+            .collect()
+    }
+
+    fn unique_requirements(&self) -> Vec<UniqueRequirement> {
+        self.plugin_dependencies
+            .iter()
+            .filter_map(|(plugin, dependency)| match dependency.kind {
+                DependencyKind::Unique(check) => Some(UniqueRequirement {
+                    plugin,
+                    unique: dependency.name,
+                    check,
+                }),
+                DependencyKind::Plugin(_) => None,
+            })
+            .collect()
+    }
+}
+
+//--------------------------------------------------
+
+pub trait PluginGroup {
+    fn build(self) -> PluginGroupBuilder;
+}
+
+pub struct PluginGroupBuilder {
+    name: &'static str,
+    plugins: Vec<PluginGroupEntry>,
+}
+
+struct PluginGroupEntry {
+    id: TypeId,
+    name: &'static str,
+    enabled: bool,
+    replaces: Option<(TypeId, &'static str)>,
+    add: Box<dyn FnOnce(&mut WorkloadBuilder)>,
+}
+
+impl PluginGroupEntry {
+    fn new<T: Plugin + 'static>(plugin: T) -> Self {
+        Self {
+            id: TypeId::of::<T>(),
+            name: std::any::type_name::<T>(),
+            enabled: true,
+            replaces: None,
+            add: Box::new(move |builder| {
+                builder.add_plugin(plugin);
+            }),
+        }
+    }
+}
+
+impl PluginGroup for PluginGroupBuilder {
+    #[inline]
+    fn build(self) -> PluginGroupBuilder {
+        self
+    }
+}
+
+impl PluginGroupBuilder {
+    pub fn start<G: PluginGroup>() -> Self {
+        Self {
+            name: std::any::type_name::<G>(),
+            plugins: Vec::new(),
+        }
+    }
+
+    fn position<T: Plugin + 'static>(&self) -> Option<usize> {
+        let id = TypeId::of::<T>();
+        self.plugins.iter().position(|entry| entry.id == id)
+    }
+
+    fn insert<T: Plugin + 'static>(mut self, index: usize, plugin: T) -> Self {
+        if let Some(old) = self.position::<T>() {
+            self.plugins.remove(old);
+        }
+
+        let index = index.min(self.plugins.len());
+        self.plugins.insert(index, PluginGroupEntry::new(plugin));
+        self
+    }
+
+    #[inline]
+    pub fn contains<T: Plugin + 'static>(&self) -> bool {
+        self.position::<T>().is_some()
+    }
+
+    #[inline]
+    pub fn enabled<T: Plugin + 'static>(&self) -> bool {
+        self.position::<T>()
+            .map(|index| self.plugins[index].enabled)
+            .unwrap_or(false)
+    }
+
+    // Adds the plugin to the end of the group or replaces the existing instance in place
+    pub fn add_plugin<T: Plugin + 'static>(mut self, plugin: T) -> Self {
+        match self.position::<T>() {
+            Some(index) => self.plugins[index] = PluginGroupEntry::new(plugin),
+            None => self.plugins.push(PluginGroupEntry::new(plugin)),
+        }
+        self
+    }
+
+    pub fn add_plugin_before<Target: Plugin + 'static, T: Plugin + 'static>(
+        self,
+        plugin: T,
+    ) -> Self {
+        let Some(index) = self.position::<Target>() else {
+            log::warn!(
+                "Plugin group '{}' doesn't contain plugin '{}'. Adding '{}' to the end",
+                self.name,
+                std::any::type_name::<Target>(),
+                std::any::type_name::<T>()
+            );
+            return self.add_plugin(plugin);
+        };
+        self.insert(index, plugin)
+    }
+
+    pub fn add_plugin_after<Target: Plugin + 'static, T: Plugin + 'static>(
+        self,
+        plugin: T,
+    ) -> Self {
+        let Some(index) = self.position::<Target>() else {
+            log::warn!(
+                "Plugin group '{}' doesn't contain plugin '{}'. Adding '{}' to the end",
+                self.name,
+                std::any::type_name::<Target>(),
+                std::any::type_name::<T>()
+            );
+            return self.add_plugin(plugin);
+        };
+        self.insert(index + 1, plugin)
+    }
+
+    pub fn disable<T: Plugin + 'static>(self) -> Self {
+        self.set_enabled::<T>(false)
+    }
+
+    pub fn enable<T: Plugin + 'static>(self) -> Self {
+        self.set_enabled::<T>(true)
+    }
+
+    fn set_enabled<T: Plugin + 'static>(mut self, enabled: bool) -> Self {
+        match self.position::<T>() {
+            Some(index) => self.plugins[index].enabled = enabled,
+            None => log::warn!(
+                "Plugin group '{}' doesn't contain plugin '{}'",
+                self.name,
+                std::any::type_name::<T>()
+            ),
+        }
+        self
+    }
+
+    // Replaces the target plugin with another, keeping its position in the group.
+    // Any other attempt to add the target plugin will be skipped.
+    pub fn replace<Target: Plugin + 'static, T: Plugin + 'static>(mut self, plugin: T) -> Self {
+        let Some(index) = self.position::<Target>() else {
+            log::warn!(
+                "Plugin group '{}' doesn't contain plugin '{}' to replace",
+                self.name,
+                std::any::type_name::<Target>(),
+            );
+            return self;
+        };
+
+        let mut entry = PluginGroupEntry::new(plugin);
+        entry.replaces = Some((TypeId::of::<Target>(), std::any::type_name::<Target>()));
+        self.plugins[index] = entry;
+
+        self
+    }
 }
 
 //====================================================================
@@ -236,18 +712,122 @@ impl<'a> WorkloadBuilder<'a> {
         &mut self,
         stage: S,
         data: StageData,
-        on_insert: Option<Box<dyn FnOnce(&mut WorkloadBuilder)>>,
+        on_insert: Option<OnStageInsert>,
     ) -> &mut Self {
+        let label = stage.as_label();
+
+        if self.inner.stages.contains_key(&label) {
+            log::error!("Stage '{:?}' has already been registered", stage);
+            return self;
+        }
+
+        if let StageOrder::Priority(priority) = &data.order {
+            let collision = self
+                .inner
+                .stages
+                .iter()
+                .find(|(_, other)| other.order == data.order);
+
+            if let Some((other, _)) = collision {
+                self.inner.stage_errors.push(format!(
+                    "Stage '{:?}' has the same priority ({}) as stage '{:?}'",
+                    stage, priority, other
+                ));
+                return self;
+            }
+        }
+
+        self.inner.log(format!(
+            "Registering stage '{:?}' - {:?}",
+            stage, data.order
+        ));
+
         if let Some(on_insert) = on_insert {
             on_insert(self);
         }
 
-        let label = stage.as_label();
+        self.inner.stages.insert(label.clone(), data);
+        self.inner.stage_registration.push(label.clone());
 
-        self.inner.stages.insert(stage.as_label(), data);
+        // Keep any workloads that were added before the stage was registered
         self.inner
             .workloads
-            .insert(label, WorkloadToBuild::new(stage));
+            .entry(label)
+            .or_insert_with(|| WorkloadToBuild::new(stage));
+
+        self
+    }
+
+    #[inline]
+    pub fn register_stage_before<S: Stage + Clone>(
+        &mut self,
+        stage: S,
+        before: impl Stage,
+    ) -> &mut Self {
+        self.register_stage(stage, StageData::before(before), None)
+    }
+
+    #[inline]
+    pub fn register_stage_after<S: Stage + Clone>(
+        &mut self,
+        stage: S,
+        after: impl Stage,
+    ) -> &mut Self {
+        self.register_stage(stage, StageData::after(after), None)
+    }
+
+    #[inline]
+    pub fn contains_stage<S: Stage>(&self, stage: S) -> bool {
+        self.inner.stages.contains_key(&stage.as_label())
+    }
+
+    pub fn set_stage_loop_condition<S: Stage>(
+        &mut self,
+        stage: S,
+        condition: impl Fn(&World) -> bool + 'static,
+    ) -> &mut Self {
+        self.modify_stage(stage, "loop condition", |data| {
+            data.loop_condition = Some(Box::new(condition))
+        })
+    }
+
+    pub fn set_stage_run_condition<S: Stage>(
+        &mut self,
+        stage: S,
+        condition: impl Fn(&World) -> bool + 'static,
+    ) -> &mut Self {
+        self.modify_stage(stage, "run condition", |data| {
+            data.run_condition = Some(Box::new(condition))
+        })
+    }
+
+    pub fn set_stage_disabled<S: Stage>(&mut self, stage: S, disabled: bool) -> &mut Self {
+        self.modify_stage(stage, "disabled", |data| data.disabled = disabled)
+    }
+
+    fn modify_stage<S: Stage>(
+        &mut self,
+        stage: S,
+        property: &str,
+        modify: impl FnOnce(&mut StageData),
+    ) -> &mut Self {
+        let label = stage.as_label();
+
+        if !self.inner.stages.contains_key(&label) {
+            log::warn!(
+                "Tried to set {} for unregistered stage '{:?}'",
+                property,
+                stage
+            );
+            return self;
+        }
+
+        self.inner
+            .log(format!("Setting {} for stage '{:?}'", property, stage));
+
+        if let Some(data) = self.inner.stages.get_mut(&label) {
+            modify(data);
+        }
 
         self
     }
@@ -258,6 +838,14 @@ impl<'a> WorkloadBuilder<'a> {
     workload_macros::create_workload_stage!(add_workload_post, SubStages::Post);
     workload_macros::create_workload_stage!(add_workload_last, SubStages::Last);
 
+    // How the built workload runner reacts to stages failing at run time
+    pub fn set_error_policy(&mut self, policy: ErrorPolicy) -> &mut Self {
+        self.inner
+            .log(format!("Setting error policy - {:?}", policy));
+        self.inner.error_policy = policy;
+        self
+    }
+
     #[inline]
     pub fn insert<U: shipyard::Unique + Send + Sync>(&mut self, unique: U) -> &mut Self {
         self.world.add_unique(unique);
@@ -291,6 +879,16 @@ mod workload_macros {
 //====================================================================
 
 impl WorkloadBuilderInner {
+    fn stage_data(&self) -> Vec<(Label, &StageOrder)> {
+        self.stage_registration
+            .iter()
+            .filter_map(|label| {
+                let data = self.stages.get(label)?;
+                Some((label.clone(), &data.order))
+            })
+            .collect()
+    }
+
     #[inline]
     pub fn get_workloads(&mut self) -> &mut HashMap<Label, WorkloadToBuild> {
         &mut self.workloads
@@ -303,6 +901,20 @@ impl WorkloadBuilderInner {
         self
     }
 
+    // Describes a condition gating workloads in a stage. Only used for schedule introspection.
+    pub fn register_workload_condition<S: shipyard::Label>(
+        &mut self,
+        workload_id: &S,
+        substage: SubStages,
+        condition: String,
+    ) -> &mut Self {
+        self.workload_conditions
+            .entry(workload_id.as_label())
+            .or_default()
+            .push((substage, condition));
+        self
+    }
+
     pub fn log(&mut self, text: String) {
         let tabs = (0..self.build_tabs).map(|_| "\t").collect::<String>();
         log::trace!("{}⌙ {}", tabs, text);
@@ -341,3 +953,252 @@ impl WorkloadBuilderInner {
 }
 
 //====================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::marker::PhantomData;
+
+    use shipyard::{AsLabel, Unique, World};
+
+    use super::{
+        Last, Plugin, PluginDependency, PluginGroup, PluginGroupBuilder, StageData, Update,
+        WorkloadBuilder,
+    };
+    use crate::{error::FeatheredError, runner::WorkloadRunner, ResMut};
+
+    #[derive(shipyard::Label, Debug, Clone, Hash, PartialEq)]
+    struct StageA;
+    impl super::Stage for StageA {}
+
+    #[derive(shipyard::Label, Debug, Clone, Hash, PartialEq)]
+    struct StageB;
+    impl super::Stage for StageB {}
+
+    #[derive(shipyard::Label, Debug, Clone, Hash, PartialEq)]
+    struct StageC;
+    impl super::Stage for StageC {}
+
+    #[derive(shipyard::Label, Debug, Clone, Hash, PartialEq)]
+    struct StageD;
+    impl super::Stage for StageD {}
+
+    #[derive(shipyard::Label, Debug, Clone, Hash, PartialEq)]
+    struct Unregistered;
+    impl super::Stage for Unregistered {}
+
+    fn build_stage_errors(register: impl FnOnce(&mut WorkloadBuilder)) -> Vec<String> {
+        let world = World::new();
+        let mut builder = WorkloadBuilder::new(&world);
+        register(&mut builder);
+
+        match builder.build() {
+            Ok(_) => Vec::new(),
+            Err(FeatheredError::Build(error)) => error.stages,
+            Err(error) => panic!("Unexpected error: {}", error),
+        }
+    }
+
+    #[test]
+    fn priority_collisions_fail_the_build() {
+        let errors = build_stage_errors(|builder| {
+            builder
+                .register_stage(StageA, StageData::from_priority(50), None)
+                .register_stage(StageB, StageData::from_priority(50), None);
+        });
+
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("StageB"));
+    }
+
+    #[test]
+    fn missing_anchors_fail_the_build() {
+        let errors = build_stage_errors(|builder| {
+            builder
+                .register_stage(StageA, StageData::from_priority(50), None)
+                .register_stage_after(StageB, Unregistered);
+        });
+
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("StageB"));
+    }
+
+    #[test]
+    fn valid_stages_build() {
+        let errors = build_stage_errors(|builder| {
+            builder
+                .register_stage(StageA, StageData::from_priority(50), None)
+                .register_stage_before(StageB, StageA);
+        });
+
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn relative_stages_keep_registration_order() {
+        let world = World::new();
+        let mut builder = WorkloadBuilder::new(&world);
+
+        builder
+            .register_stage(Update, StageData::from_priority(20), None)
+            .register_stage(Last, StageData::from_priority(40), None)
+            .register_stage_after(StageA, Update)
+            .register_stage_after(StageB, Update)
+            .register_stage_after(StageC, StageA)
+            .register_stage_after(StageD, Update);
+
+        let (_, order) = crate::runner::resolve_stage_order(&builder.inner.stage_data()).unwrap();
+
+        assert_eq!(
+            order,
+            vec![
+                Update.as_label(),
+                StageA.as_label(),
+                StageC.as_label(),
+                StageB.as_label(),
+                StageD.as_label(),
+                Last.as_label(),
+            ]
+        );
+    }
+
+    //--------------------------------------------------
+
+    #[derive(Unique, Default)]
+    struct Built(Vec<u8>);
+
+    #[derive(Unique)]
+    struct Config;
+
+    struct Recorded<const ID: u8>;
+
+    impl<const ID: u8> Plugin for Recorded<ID> {
+        fn build_plugin(self, builder: &mut WorkloadBuilder) {
+            builder
+                .get_world()
+                .borrow::<ResMut<Built>>()
+                .unwrap()
+                .0
+                .push(ID);
+        }
+    }
+
+    struct Requires<P>(PhantomData<P>);
+
+    impl<P: Plugin + 'static> Plugin for Requires<P> {
+        fn build_plugin(self, _: &mut WorkloadBuilder) {}
+
+        fn dependencies(&self) -> Vec<PluginDependency> {
+            vec![PluginDependency::plugin::<P>()]
+        }
+    }
+
+    struct RequiresConfig;
+
+    impl Plugin for RequiresConfig {
+        fn build_plugin(self, _: &mut WorkloadBuilder) {}
+
+        fn dependencies(&self) -> Vec<PluginDependency> {
+            vec![PluginDependency::unique::<Config>()]
+        }
+    }
+
+    struct Group;
+
+    impl PluginGroup for Group {
+        fn build(self) -> PluginGroupBuilder {
+            PluginGroupBuilder::start::<Self>()
+                .add_plugin(Recorded::<1>)
+                .add_plugin(Recorded::<2>)
+                .add_plugin(Recorded::<3>)
+        }
+    }
+
+    fn build_plugins(
+        world: &World,
+        register: impl FnOnce(&mut WorkloadBuilder),
+    ) -> Result<WorkloadRunner, FeatheredError> {
+        world.add_unique(Built::default());
+
+        let mut builder = WorkloadBuilder::new(world);
+        register(&mut builder);
+        builder.build()
+    }
+
+    fn missing_plugins(result: Result<WorkloadRunner, FeatheredError>) -> Vec<String> {
+        match result {
+            Ok(_) => Vec::new(),
+            Err(FeatheredError::Build(error)) => error.missing_plugins,
+            Err(error) => panic!("Unexpected error: {}", error),
+        }
+    }
+
+    #[test]
+    fn missing_plugin_dependencies_fail_the_build() {
+        let errors = missing_plugins(build_plugins(&World::new(), |builder| {
+            builder.add_plugin(Requires::<Recorded<1>>(PhantomData));
+        }));
+
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("Recorded<1>"), "{}", errors[0]);
+        assert!(errors[0].contains("was never added"), "{}", errors[0]);
+
+        // Dependencies can be added after the plugin needing them
+        let errors = missing_plugins(build_plugins(&World::new(), |builder| {
+            builder
+                .add_plugin(Requires::<Recorded<1>>(PhantomData))
+                .add_plugin(Recorded::<1>);
+        }));
+
+        assert!(errors.is_empty(), "{:?}", errors);
+    }
+
+    #[test]
+    fn missing_uniques_fail_prep() {
+        let world = World::new();
+        let mut runner = build_plugins(&world, |builder| {
+            builder.add_plugin(RequiresConfig);
+        })
+        .unwrap();
+
+        match runner.prep(&world) {
+            Err(FeatheredError::MissingUniques(missing)) => {
+                assert_eq!(missing.len(), 1);
+                assert!(missing[0].contains("Config"), "{}", missing[0]);
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+
+        world.add_unique(Config);
+        assert!(runner.prep(&world).is_ok());
+    }
+
+    #[test]
+    fn plugin_groups_can_disable_replace_and_insert() {
+        let world = World::new();
+        let errors = missing_plugins(build_plugins(&world, |builder| {
+            builder
+                .add_plugins(
+                    Group
+                        .build()
+                        .disable::<Recorded<1>>()
+                        .replace::<Recorded<2>, _>(Recorded::<4>)
+                        .add_plugin_before::<Recorded<3>, _>(Recorded::<5>),
+                )
+                // Disabled and replaced plugins stay out when added again
+                .add_plugin(Recorded::<1>)
+                .add_plugin(Recorded::<2>)
+                .add_plugin(Requires::<Recorded<1>>(PhantomData))
+                .add_plugin(Requires::<Recorded<2>>(PhantomData));
+        }));
+
+        assert_eq!(
+            world.run(|built: ResMut<Built>| built.0.clone()),
+            vec![4, 5, 3]
+        );
+
+        // Depending on a replaced plugin is fine, depending on a disabled one isn't
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("Recorded<1>"), "{}", errors[0]);
+        assert!(errors[0].contains("has been disabled"), "{}", errors[0]);
+    }
+}
@@ -0,0 +1,314 @@
+//====================================================================
+
+use std::sync::Mutex;
+
+use shipyard::{
+    info::TypeInfo, AllStorages, AllStoragesViewMut, Borrow, BorrowInfo, EntityId, IntoWorkload,
+    SharedBorrow, TrackingTimestamp, TupleAddComponent, TupleRemove, Unique, Workload,
+};
+
+use crate::{builder::SubStages, Res};
+
+//====================================================================
+
+pub type Command = Box<dyn FnOnce(&mut AllStorages) + Send>;
+pub type EntityCommand = Box<dyn FnOnce(EntityId, &mut AllStorages) + Send>;
+
+//====================================================================
+
+// Commands recorded by systems, waiting to be applied at the end of the current substage
+#[derive(Unique, Default)]
+pub struct CommandQueue {
+    commands: Mutex<Vec<Command>>,
+}
+
+impl CommandQueue {
+    #[inline]
+    pub fn push(&self, command: Command) {
+        self.commands.lock().unwrap().push(command);
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.commands.lock().unwrap().len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    fn append(&self, commands: &mut Vec<Command>) {
+        self.commands.lock().unwrap().append(commands);
+    }
+
+    #[inline]
+    fn take(&self) -> Vec<Command> {
+        std::mem::take(&mut *self.commands.lock().unwrap())
+    }
+}
+
+//--------------------------------------------------
+
+// Commands are buffered per system and only pushed to the queue once the system has finished,
+// so commands from a single system are always applied together and in order.
+struct CommandBuffer(Vec<Command>);
+
+impl Borrow for CommandBuffer {
+    type View<'a> = CommandBuffer;
+
+    #[inline]
+    fn borrow<'a>(
+        _all_storages: &'a AllStorages,
+        _all_borrow: Option<SharedBorrow<'a>>,
+        _last_run: Option<TrackingTimestamp>,
+        _current: TrackingTimestamp,
+    ) -> Result<Self::View<'a>, shipyard::error::GetStorage> {
+        Ok(CommandBuffer(Vec::new()))
+    }
+}
+
+// SAFE: Buffer doesn't borrow any storages
+unsafe impl BorrowInfo for CommandBuffer {
+    #[inline]
+    fn borrow_info(_info: &mut Vec<TypeInfo>) {}
+
+    #[inline]
+    fn enable_tracking(
+        _enable_tracking_fn: &mut Vec<fn(&AllStorages) -> Result<(), shipyard::error::GetStorage>>,
+    ) {
+    }
+}
+
+// Only borrows the command queue immutably, so systems using commands can still run in parallel
+#[derive(Borrow, BorrowInfo)]
+pub struct Commands<'v> {
+    queue: Res<'v, CommandQueue>,
+    buffer: CommandBuffer,
+}
+
+impl Drop for Commands<'_> {
+    fn drop(&mut self) {
+        if !self.buffer.0.is_empty() {
+            self.queue.append(&mut self.buffer.0);
+        }
+    }
+}
+
+impl Commands<'_> {
+    #[inline]
+    pub fn add<C>(&mut self, command: C) -> &mut Self
+    where
+        C: FnOnce(&mut AllStorages) + Send + 'static,
+    {
+        self.buffer.0.push(Box::new(command));
+        self
+    }
+
+    // The id of the new entity isn't known until the commands are applied.
+    // Use `EntityCommands::add` to run further commands using the id.
+    pub fn spawn<T>(&mut self, components: T) -> EntityCommands<'_>
+    where
+        T: TupleAddComponent + Send + 'static,
+    {
+        EntityCommands {
+            buffer: &mut self.buffer.0,
+            target: EntityTarget::Spawn(Box::new(move |all_storages| {
+                all_storages.add_entity(components)
+            })),
+            commands: Vec::new(),
+        }
+    }
+
+    // Commands for an existing entity are ignored if it's been despawned by the time they're applied
+    #[inline]
+    pub fn entity(&mut self, entity: EntityId) -> EntityCommands<'_> {
+        EntityCommands {
+            buffer: &mut self.buffer.0,
+            target: EntityTarget::Existing(entity),
+            commands: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn despawn(&mut self, entity: EntityId) -> &mut Self {
+        self.add(move |all_storages| {
+            all_storages.delete_entity(entity);
+        })
+    }
+
+    #[inline]
+    pub fn insert<T>(&mut self, entity: EntityId, components: T) -> &mut Self
+    where
+        T: TupleAddComponent + Send + 'static,
+    {
+        self.entity(entity).insert(components);
+        self
+    }
+
+    #[inline]
+    pub fn remove<T>(&mut self, entity: EntityId) -> &mut Self
+    where
+        T: TupleRemove + 'static,
+    {
+        self.entity(entity).remove::<T>();
+        self
+    }
+
+    #[inline]
+    pub fn insert_unique<U: Unique + Send + Sync>(&mut self, unique: U) -> &mut Self {
+        self.add(move |all_storages| all_storages.add_unique(unique))
+    }
+
+    #[inline]
+    pub fn remove_unique<U: Unique + Send + Sync>(&mut self) -> &mut Self {
+        self.add(|all_storages| {
+            if all_storages.remove_unique::<U>().is_err() {
+                log::warn!(
+                    "Unable to remove unique '{}' - not found",
+                    std::any::type_name::<U>()
+                );
+            }
+        })
+    }
+}
+
+//--------------------------------------------------
+
+enum EntityTarget {
+    Spawn(Box<dyn FnOnce(&mut AllStorages) -> EntityId + Send>),
+    Existing(EntityId),
+}
+
+// Records commands for a single entity. Pushed to the system's buffer as one command when dropped.
+pub struct EntityCommands<'a> {
+    buffer: &'a mut Vec<Command>,
+    target: EntityTarget,
+    commands: Vec<EntityCommand>,
+}
+
+impl Drop for EntityCommands<'_> {
+    fn drop(&mut self) {
+        let commands = std::mem::take(&mut self.commands);
+
+        let command: Command =
+            match std::mem::replace(&mut self.target, EntityTarget::Existing(EntityId::dead())) {
+                EntityTarget::Spawn(spawn) => Box::new(move |all_storages| {
+                    let entity = spawn(all_storages);
+                    apply_entity_commands(entity, commands, all_storages);
+                }),
+
+                EntityTarget::Existing(entity) => {
+                    if commands.is_empty() {
+                        return;
+                    }
+
+                    Box::new(move |all_storages| {
+                        apply_entity_commands(entity, commands, all_storages)
+                    })
+                }
+            };
+
+        self.buffer.push(command);
+    }
+}
+
+fn apply_entity_commands(
+    entity: EntityId,
+    commands: Vec<EntityCommand>,
+    all_storages: &mut AllStorages,
+) {
+    for command in commands {
+        if !all_storages.is_entity_alive(entity) {
+            log::warn!("Skipping commands for despawned entity {:?}", entity);
+            return;
+        }
+
+        command(entity, all_storages);
+    }
+}
+
+impl EntityCommands<'_> {
+    // None if the entity is being spawned
+    #[inline]
+    pub fn id(&self) -> Option<EntityId> {
+        match self.target {
+            EntityTarget::Spawn(_) => None,
+            EntityTarget::Existing(entity) => Some(entity),
+        }
+    }
+
+    #[inline]
+    pub fn add<C>(&mut self, command: C) -> &mut Self
+    where
+        C: FnOnce(EntityId, &mut AllStorages) + Send + 'static,
+    {
+        self.commands.push(Box::new(command));
+        self
+    }
+
+    #[inline]
+    pub fn insert<T>(&mut self, components: T) -> &mut Self
+    where
+        T: TupleAddComponent + Send + 'static,
+    {
+        self.add(move |entity, all_storages| all_storages.add_component(entity, components))
+    }
+
+    #[inline]
+    pub fn remove<T>(&mut self) -> &mut Self
+    where
+        T: TupleRemove + 'static,
+    {
+        self.add(|entity, all_storages| {
+            all_storages.remove::<T>(entity);
+        })
+    }
+
+    // Any commands added after this are skipped
+    #[inline]
+    pub fn despawn(&mut self) -> &mut Self {
+        self.add(|entity, all_storages| {
+            all_storages.delete_entity(entity);
+        })
+    }
+}
+
+//====================================================================
+
+// Added to the end of every substage when the workloads are built. Shipyard identifies systems
+// by their type, so each substage needs its own instance of the system.
+pub(crate) fn apply_commands_workload(substage: SubStages) -> Workload {
+    match substage {
+        SubStages::First => sys_apply_substage_commands::<0>.into_workload(),
+        SubStages::Pre => sys_apply_substage_commands::<1>.into_workload(),
+        SubStages::Main => sys_apply_substage_commands::<2>.into_workload(),
+        SubStages::Post => sys_apply_substage_commands::<3>.into_workload(),
+        SubStages::Last => sys_apply_substage_commands::<4>.into_workload(),
+    }
+}
+
+#[inline]
+fn sys_apply_substage_commands<const SUBSTAGE: u8>(all_storages: AllStoragesViewMut) {
+    sys_apply_commands(all_storages);
+}
+
+pub fn sys_apply_commands(mut all_storages: AllStoragesViewMut) {
+    loop {
+        let commands = match all_storages.borrow::<Res<CommandQueue>>() {
+            Ok(queue) => queue.take(),
+            Err(_) => return,
+        };
+
+        if commands.is_empty() {
+            return;
+        }
+
+        commands
+            .into_iter()
+            .for_each(|command| command(&mut all_storages));
+    }
+}
+
+//====================================================================
@@ -0,0 +1,132 @@
+//====================================================================
+
+use std::{error::Error, fmt::Display};
+
+use shipyard::error::{AddWorkload, GetStorage, Run, RunWorkload};
+
+//====================================================================
+
+#[derive(Debug)]
+pub enum FeatheredError {
+    // Every problem found while building the workloads
+    Build(BuildError),
+    // Uniques required by plugins that weren't present before the setup stages ran
+    MissingUniques(Vec<String>),
+    // A stage failed to run. Any systems after the failing one in that stage were skipped.
+    Stage {
+        stage: String,
+        error: Box<RunWorkload>,
+    },
+}
+
+impl FeatheredError {
+    // Name of the storage that was missing, if that caused a stage to fail
+    pub fn missing_storage(&self) -> Option<&'static str> {
+        match self {
+            FeatheredError::Stage { error, .. } => match error.as_ref() {
+                RunWorkload::Run((_, Run::GetStorage(GetStorage::MissingStorage { name, .. }))) => {
+                    *name
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    // Name of the system that failed, if a stage failed while running a system
+    pub fn failing_system(&self) -> Option<String> {
+        match self {
+            FeatheredError::Stage { error, .. } => match error.as_ref() {
+                RunWorkload::Run((system, _)) => Some(format!("{:?}", system)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+impl Error for FeatheredError {}
+
+impl Display for FeatheredError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FeatheredError::Build(error) => write!(f, "{}", error),
+            FeatheredError::MissingUniques(missing) => {
+                write!(f, "Missing plugin dependencies:")?;
+                missing
+                    .iter()
+                    .try_for_each(|missing| write!(f, "\n    {}", missing))
+            }
+            FeatheredError::Stage { stage, error } => match error.as_ref() {
+                RunWorkload::Run((system, error)) => write!(
+                    f,
+                    "Stage '{}' failed in system '{:?}': {}",
+                    stage, system, error
+                ),
+                error => write!(f, "Stage '{}' failed: {}", stage, error),
+            },
+        }
+    }
+}
+
+//--------------------------------------------------
+
+#[derive(Debug, Default)]
+pub struct BuildError {
+    pub missing_plugins: Vec<String>,
+    // Stages that collide with another stage's priority or can't be placed in the order
+    pub stages: Vec<String>,
+    pub workloads: Vec<WorkloadError>,
+}
+
+impl BuildError {
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.missing_plugins.is_empty() && self.stages.is_empty() && self.workloads.is_empty()
+    }
+}
+
+impl Display for BuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Failed to build workloads:")?;
+
+        self.missing_plugins
+            .iter()
+            .try_for_each(|missing| write!(f, "\n    {}", missing))?;
+
+        self.stages
+            .iter()
+            .try_for_each(|stage| write!(f, "\n    {}", stage))?;
+
+        self.workloads
+            .iter()
+            .try_for_each(|workload| write!(f, "\n    {}", workload))
+    }
+}
+
+#[derive(Debug)]
+pub struct WorkloadError {
+    pub workload: String,
+    pub error: AddWorkload,
+}
+
+impl Display for WorkloadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Workload '{}' - {}", self.workload, self.error)
+    }
+}
+
+//--------------------------------------------------
+
+// How the workload runner reacts to a stage failing at run time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorPolicy {
+    // Log the error (once per stage) and carry on with the next stage
+    #[default]
+    LogAndSkip,
+    // Stop running and return the error so the runner can shut down
+    Stop,
+    Panic,
+}
+
+//====================================================================
@@ -1,8 +1,17 @@
 //====================================================================
 
-use std::fmt::Debug;
+use std::{
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
 
-use shipyard::{Borrow, BorrowInfo, IntoWorkload, Unique, WorkloadModificator};
+use shipyard::{
+    info::TypeInfo, AllStorages, Borrow, BorrowInfo, IntoWorkload, SharedBorrow, TrackingTimestamp,
+    Unique, WorkloadModificator,
+};
 
 use crate::{
     builder::{First, SubStages, WorkloadBuilder},
@@ -15,7 +24,12 @@ pub use feathered_proc::Event;
 pub trait Event: 'static + Send + Sync + std::fmt::Debug {}
 
 pub trait EventBuilder {
-    fn register_event<E: Event>(&mut self) -> &mut Self;
+    #[inline]
+    fn register_event<E: Event>(&mut self) -> &mut Self {
+        self.register_event_with_retention::<E>(DEFAULT_EVENT_RETENTION)
+    }
+
+    fn register_event_with_retention<E: Event>(&mut self, retention: u32) -> &mut Self;
 
     #[inline]
     fn event_workload<E: Event>(
@@ -34,22 +48,21 @@ pub trait EventBuilder {
     ) -> &mut Self;
 }
 
-impl<'a> EventBuilder for WorkloadBuilder<'a> {
-    fn register_event<E: Event>(&mut self) -> &mut Self {
+impl EventBuilder for WorkloadBuilder<'_> {
+    fn register_event_with_retention<E: Event>(&mut self, retention: u32) -> &mut Self {
         self.get_inner().log(format!(
-            "Registering event type '{}'",
-            std::any::type_name::<E>()
+            "Registering event type '{}' - retained for {} frames",
+            std::any::type_name::<E>(),
+            retention
         ));
 
-        self.get_world().add_unique(EventHandle::<E> {
-            pending_events: Vec::new(),
-            events: Vec::new(),
-        });
+        self.get_world()
+            .add_unique(EventHandle::<E>::new(retention));
 
         self.get_inner().add_workload_sub(
             First,
             SubStages::Main,
-            (sys_setup_events::<E>).into_workload(),
+            (sys_update_events::<E>).into_workload(),
             true,
         );
 
@@ -69,10 +82,16 @@ impl<'a> EventBuilder for WorkloadBuilder<'a> {
             substage
         ));
 
+        self.get_inner().register_workload_condition(
+            &workload_id,
+            substage,
+            format!("event '{}' sent", std::any::type_name::<E>()),
+        );
+
         self.get_inner().add_workload_sub(
             workload_id,
             substage,
-            workload.skip_if(sys_check_skip_event::<E>),
+            workload.skip_if(skip_without_new_events::<E>()),
             true,
         );
 
@@ -83,10 +102,27 @@ impl<'a> EventBuilder for WorkloadBuilder<'a> {
 //====================================================================
 
 pub trait ReadEvents<E: Event> {
-    fn iter(&self) -> std::slice::Iter<E>;
-    fn events(&self) -> &Vec<E>;
-    fn first(&self) -> Option<&E>;
-    fn last(&self) -> Option<&E>;
+    fn iter(&self) -> EventIter<'_, E>;
+
+    #[inline]
+    fn first(&self) -> Option<&E> {
+        self.iter().next()
+    }
+
+    #[inline]
+    fn last(&self) -> Option<&E> {
+        self.iter().next_back()
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.first().is_none()
+    }
 }
 
 pub trait WriteEvents<E: Event> {
@@ -97,9 +133,10 @@ pub trait WriteEvents<E: Event> {
 
 trait GetEventHandle<E: Event> {
     fn handle(&self) -> &EventHandle<E>;
+    fn cursor(&self) -> &EventCursor;
 }
 
-trait GetEventHandleMut<E: Event> {
+trait GetEventHandleMut<E: Event>: GetEventHandle<E> {
     fn handle_mut(&mut self) -> &mut EventHandle<E>;
 }
 
@@ -111,23 +148,11 @@ where
     T: GetEventHandle<E>,
 {
     #[inline]
-    fn iter(&self) -> std::slice::Iter<E> {
-        self.handle().events.iter()
-    }
-
-    #[inline]
-    fn events(&self) -> &Vec<E> {
-        &self.handle().events
-    }
-
-    #[inline]
-    fn first(&self) -> Option<&E> {
-        self.handle().events.first()
-    }
-
-    #[inline]
-    fn last(&self) -> Option<&E> {
-        self.handle().events.last()
+    fn iter(&self) -> EventIter<'_, E> {
+        EventIter {
+            inner: self.handle().events.iter(),
+            cursor: *self.cursor(),
+        }
     }
 }
 
@@ -138,81 +163,245 @@ where
 {
     #[inline]
     fn send_event(&mut self, event: E) {
-        self.handle_mut().pending_events.push(event)
+        let timestamp = self.cursor().current;
+        self.handle_mut().push(timestamp, event);
     }
 }
 
 //====================================================================
 
+const DEFAULT_EVENT_RETENTION: u32 = 2;
+const MIN_EVENT_RETENTION: u32 = 2;
+
+struct EventInstance<E: Event> {
+    timestamp: TrackingTimestamp,
+    frame: u64,
+    event: E,
+}
+
 #[derive(Unique)]
 pub struct EventHandle<E: Event> {
-    pending_events: Vec<E>,
-    events: Vec<E>,
+    events: Vec<EventInstance<E>>,
+    frame: u64,
+    retention: u32,
+    // Total events ever sent, used by event workloads to check for new events
+    sent: u64,
+}
+
+// Shipyard tracks the last time each system ran, which is used as a per system cursor
+// so each reader only sees the events sent since it last ran.
+#[derive(Clone, Copy)]
+struct EventCursor {
+    last_run: Option<TrackingTimestamp>,
+    current: TrackingTimestamp,
+}
+
+impl EventCursor {
+    #[inline]
+    fn can_see(&self, timestamp: TrackingTimestamp) -> bool {
+        match self.last_run {
+            Some(last_run) => timestamp.is_within(last_run, self.current),
+            None => true,
+        }
+    }
+}
+
+impl Borrow for EventCursor {
+    type View<'a> = EventCursor;
+
+    #[inline]
+    fn borrow<'a>(
+        _all_storages: &'a AllStorages,
+        _all_borrow: Option<SharedBorrow<'a>>,
+        last_run: Option<TrackingTimestamp>,
+        current: TrackingTimestamp,
+    ) -> Result<Self::View<'a>, shipyard::error::GetStorage> {
+        Ok(EventCursor { last_run, current })
+    }
+}
+
+// SAFE: Cursor doesn't borrow any storages
+unsafe impl BorrowInfo for EventCursor {
+    #[inline]
+    fn borrow_info(_info: &mut Vec<TypeInfo>) {}
+
+    #[inline]
+    fn enable_tracking(
+        _enable_tracking_fn: &mut Vec<fn(&AllStorages) -> Result<(), shipyard::error::GetStorage>>,
+    ) {
+    }
 }
 
 #[derive(Borrow, BorrowInfo)]
 pub struct EventReader<'v, E: Event> {
     handle: Res<'v, EventHandle<E>>,
+    cursor: EventCursor,
 }
 
 #[derive(Borrow, BorrowInfo)]
 pub struct EventSender<'v, E: Event> {
     handle: ResMut<'v, EventHandle<E>>,
+    cursor: EventCursor,
+}
+
+// Reader that can also take ownership of the events it hasn't seen yet.
+// Drained events are removed for every other reader as well.
+#[derive(Borrow, BorrowInfo)]
+pub struct EventConsumer<'v, E: Event> {
+    handle: ResMut<'v, EventHandle<E>>,
+    cursor: EventCursor,
 }
 
 //--------------------------------------------------
 
-impl<E: Event> GetEventHandle<E> for EventHandle<E> {
+impl<E: Event> GetEventHandle<E> for EventReader<'_, E> {
     #[inline]
     fn handle(&self) -> &EventHandle<E> {
-        self
+        &self.handle
     }
-}
-impl<E: Event> GetEventHandleMut<E> for EventHandle<E> {
+
     #[inline]
-    fn handle_mut(&mut self) -> &mut EventHandle<E> {
-        self
+    fn cursor(&self) -> &EventCursor {
+        &self.cursor
     }
 }
 
-impl<'v, E: Event> GetEventHandle<E> for EventReader<'v, E> {
+impl<E: Event> GetEventHandle<E> for EventSender<'_, E> {
     #[inline]
     fn handle(&self) -> &EventHandle<E> {
         &self.handle
     }
+
+    #[inline]
+    fn cursor(&self) -> &EventCursor {
+        &self.cursor
+    }
+}
+impl<E: Event> GetEventHandleMut<E> for EventSender<'_, E> {
+    #[inline]
+    fn handle_mut(&mut self) -> &mut EventHandle<E> {
+        &mut self.handle
+    }
 }
 
-impl<'v, E: Event> GetEventHandle<E> for EventSender<'v, E> {
+impl<E: Event> GetEventHandle<E> for EventConsumer<'_, E> {
     #[inline]
     fn handle(&self) -> &EventHandle<E> {
         &self.handle
     }
+
+    #[inline]
+    fn cursor(&self) -> &EventCursor {
+        &self.cursor
+    }
 }
-impl<'v, E: Event> GetEventHandleMut<E> for EventSender<'v, E> {
+
+impl<E: Event> EventConsumer<'_, E> {
+    pub fn drain(&mut self) -> std::vec::IntoIter<E> {
+        let cursor = self.cursor;
+        let (drained, kept) = self
+            .handle
+            .events
+            .drain(..)
+            .partition::<Vec<_>, _>(|instance| cursor.can_see(instance.timestamp));
+
+        self.handle.events = kept;
+
+        drained
+            .into_iter()
+            .map(|instance| instance.event)
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+//--------------------------------------------------
+
+pub struct EventIter<'a, E: Event> {
+    inner: std::slice::Iter<'a, EventInstance<E>>,
+    cursor: EventCursor,
+}
+
+impl<'a, E: Event> Iterator for EventIter<'a, E> {
+    type Item = &'a E;
+
     #[inline]
-    fn handle_mut(&mut self) -> &mut EventHandle<E> {
-        &mut self.handle
+    fn next(&mut self) -> Option<Self::Item> {
+        let cursor = self.cursor;
+        self.inner
+            .find(|instance| cursor.can_see(instance.timestamp))
+            .map(|instance| &instance.event)
+    }
+}
+
+impl<E: Event> DoubleEndedIterator for EventIter<'_, E> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let cursor = self.cursor;
+        self.inner
+            .rfind(|instance| cursor.can_see(instance.timestamp))
+            .map(|instance| &instance.event)
     }
 }
 
 //====================================================================
 
 impl<E: Event> EventHandle<E> {
+    fn new(retention: u32) -> Self {
+        if retention < MIN_EVENT_RETENTION {
+            log::warn!(
+                "Event retention for '{}' must be at least {} frames",
+                std::any::type_name::<E>(),
+                MIN_EVENT_RETENTION
+            );
+        }
+
+        Self {
+            events: Vec::new(),
+            frame: 0,
+            retention: retention.max(MIN_EVENT_RETENTION),
+            sent: 0,
+        }
+    }
+
     #[inline]
-    fn setup_events(&mut self) {
-        std::mem::swap(&mut self.pending_events, &mut self.events);
-        self.pending_events.clear();
+    pub fn retention(&self) -> u32 {
+        self.retention
+    }
+
+    #[inline]
+    fn push(&mut self, timestamp: TrackingTimestamp, event: E) {
+        self.sent += 1;
+        self.events.push(EventInstance {
+            timestamp,
+            frame: self.frame,
+            event,
+        });
+    }
+
+    #[inline]
+    fn update_events(&mut self) {
+        self.frame += 1;
+
+        let (frame, retention) = (self.frame, self.retention as u64);
+        self.events
+            .retain(|instance| frame - instance.frame < retention);
     }
 }
 
 #[inline]
-fn sys_setup_events<E: Event>(mut handle: ResMut<EventHandle<E>>) {
-    handle.setup_events();
+fn sys_update_events<E: Event>(mut handle: ResMut<EventHandle<E>>) {
+    handle.update_events();
 }
 
-#[inline]
-pub fn sys_check_skip_event<E: Event>(handle: Res<EventHandle<E>>) -> bool {
-    handle.events.is_empty()
+// Workload run conditions share a single last run between all systems in the workload,
+// so this can't use a cursor. Instead each event workload keeps count of the events sent
+// when it last checked and only runs once more have been sent, rather than every frame
+// an event is retained for. Readers in the workload still only see new events.
+fn skip_without_new_events<E: Event>() -> impl Fn(Res<EventHandle<E>>) -> bool + Clone {
+    let checked = Arc::new(AtomicU64::new(0));
+
+    move |handle: Res<EventHandle<E>>| checked.swap(handle.sent, Ordering::Relaxed) == handle.sent
 }
 
 //====================================================================
@@ -1,8 +1,14 @@
 //====================================================================
 
 pub mod builder;
+pub mod commands;
+pub mod error;
 pub mod events;
+pub mod profiling;
 pub mod runner;
+pub mod scene;
+pub mod schedule;
+pub mod state;
 pub mod tools;
 
 //====================================================================
@@ -10,8 +16,10 @@ pub mod tools;
 pub mod prelude {
     pub use crate::{
         builder::{
-            First, Last, Plugin, Render, RenderPrep, Setup, SubStages, Update, WorkloadBuilder,
+            First, FixedUpdate, Last, Plugin, PluginDependency, PluginGroup, PluginGroupBuilder,
+            Render, RenderPrep, Setup, SubStages, Update, WorkloadBuilder,
         },
+        commands::Commands,
         tools::UniqueTools,
         Res, ResMut,
     };
@@ -0,0 +1,558 @@
+//====================================================================
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Write,
+};
+
+use shipyard::Unique;
+use web_time::{Duration, Instant};
+
+//====================================================================
+
+pub const DEFAULT_STATS_WINDOW: usize = 120;
+pub const DEFAULT_TRACE_FRAMES: usize = 10;
+
+// Stage timings are recorded by the workload runner while this unique exists and is enabled.
+// System timings additionally require the 'profiling' feature and the system span layer
+// to be installed (see 'init_system_profiling').
+#[derive(Unique)]
+pub struct FrameStats {
+    enabled: bool,
+    window: usize,
+    trace_frames: usize,
+    epoch: Instant,
+    frame_count: u64,
+
+    frame: TimingStats,
+    stages: Vec<(String, TimingStats)>,
+    systems: HashMap<String, TimingStats>,
+
+    trace: VecDeque<Vec<TraceEvent>>,
+}
+
+impl Default for FrameStats {
+    fn default() -> Self {
+        Self::new(DEFAULT_STATS_WINDOW)
+    }
+}
+
+impl FrameStats {
+    pub fn new(window: usize) -> Self {
+        let window = window.max(1);
+
+        Self {
+            enabled: true,
+            window,
+            trace_frames: DEFAULT_TRACE_FRAMES,
+            epoch: Instant::now(),
+            frame_count: 0,
+            frame: TimingStats::new(window),
+            stages: Vec::new(),
+            systems: HashMap::new(),
+            trace: VecDeque::new(),
+        }
+    }
+
+    // Number of most recent frames kept for the chrome trace export
+    pub fn with_trace_frames(mut self, frames: usize) -> Self {
+        self.trace_frames = frames;
+        self
+    }
+
+    #[inline]
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    #[inline]
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    #[inline]
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    #[inline]
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    #[inline]
+    pub fn frame(&self) -> &TimingStats {
+        &self.frame
+    }
+
+    pub fn stage(&self, name: &str) -> Option<&TimingStats> {
+        self.stages
+            .iter()
+            .find(|(stage, _)| stage == name)
+            .map(|(_, stats)| stats)
+    }
+
+    // Stages in the order they first ran
+    pub fn stages(&self) -> impl Iterator<Item = (&str, &TimingStats)> {
+        self.stages
+            .iter()
+            .map(|(name, stats)| (name.as_str(), stats))
+    }
+
+    #[inline]
+    pub fn system(&self, name: &str) -> Option<&TimingStats> {
+        self.systems.get(name)
+    }
+
+    pub fn systems(&self) -> impl Iterator<Item = (&str, &TimingStats)> {
+        self.systems
+            .iter()
+            .map(|(name, stats)| (name.as_str(), stats))
+    }
+
+    // Systems sorted by their rolling average, slowest first
+    pub fn slowest_systems(&self) -> Vec<(&str, &TimingStats)> {
+        let mut systems = self.systems().collect::<Vec<_>>();
+        systems.sort_by_key(|(_, stats)| std::cmp::Reverse(stats.average()));
+        systems
+    }
+
+    pub fn clear(&mut self) {
+        self.frame = TimingStats::new(self.window);
+        self.stages.clear();
+        self.systems.clear();
+        self.trace.clear();
+    }
+
+    pub(crate) fn record_frame(&mut self, record: FrameRecord) {
+        self.frame_count += 1;
+        self.frame.push(record.duration);
+
+        let mut trace = vec![self.trace_event(
+            "Frame",
+            TraceCategory::Frame,
+            0,
+            record.start,
+            record.duration,
+        )];
+
+        record.stages.iter().for_each(|stage| {
+            match self.stages.iter_mut().find(|(name, _)| *name == stage.name) {
+                Some((_, stats)) => stats.push(stage.duration),
+                None => {
+                    let mut stats = TimingStats::new(self.window);
+                    stats.push(stage.duration);
+                    self.stages.push((stage.name.clone(), stats));
+                }
+            }
+
+            trace.push(self.trace_event(
+                &stage.name,
+                TraceCategory::Stage,
+                0,
+                stage.start,
+                stage.duration,
+            ));
+        });
+
+        // Systems can run multiple times a frame (looping stages), so sum them first
+        let mut system_totals = HashMap::<&str, Duration>::new();
+
+        record.systems.iter().for_each(|system| {
+            *system_totals.entry(system.name.as_str()).or_default() += system.duration;
+
+            trace.push(self.trace_event(
+                &system.name,
+                TraceCategory::System,
+                system.thread,
+                system.start,
+                system.duration,
+            ));
+        });
+
+        system_totals.into_iter().for_each(|(name, duration)| {
+            self.systems
+                .entry(name.to_string())
+                .or_insert_with(|| TimingStats::new(self.window))
+                .push(duration)
+        });
+
+        if self.trace_frames > 0 {
+            if self.trace.len() >= self.trace_frames {
+                self.trace.pop_front();
+            }
+            self.trace.push_back(trace);
+        }
+    }
+
+    fn trace_event(
+        &self,
+        name: &str,
+        category: TraceCategory,
+        thread: usize,
+        start: Instant,
+        duration: Duration,
+    ) -> TraceEvent {
+        TraceEvent {
+            name: name.to_string(),
+            category,
+            thread,
+            start: start.saturating_duration_since(self.epoch),
+            duration,
+        }
+    }
+
+    // Chrome trace event format. Can be loaded in chrome://tracing or https://ui.perfetto.dev
+    pub fn to_chrome_trace(&self) -> String {
+        let mut threads = self
+            .trace
+            .iter()
+            .flatten()
+            .map(|event| event.thread)
+            .collect::<Vec<_>>();
+        threads.sort();
+        threads.dedup();
+
+        let metadata = threads.into_iter().map(|thread| {
+            let name = match thread {
+                0 => "Main".to_string(),
+                thread => format!("Worker {}", thread),
+            };
+
+            format!(
+                "{{\"name\":\"thread_name\",\"ph\":\"M\",\"pid\":0,\"tid\":{},\"args\":{{\"name\":\"{}\"}}}}",
+                thread, name
+            )
+        });
+
+        let events = self.trace.iter().flatten().map(|event| {
+            format!(
+                "{{\"name\":\"{}\",\"cat\":\"{}\",\"ph\":\"X\",\"pid\":0,\"tid\":{},\"ts\":{:.3},\"dur\":{:.3}}}",
+                escape_json(&event.name),
+                event.category.as_str(),
+                event.thread,
+                event.start.as_secs_f64() * 1_000_000.,
+                event.duration.as_secs_f64() * 1_000_000.,
+            )
+        });
+
+        let mut json = String::from("{\"traceEvents\":[");
+
+        metadata
+            .chain(events)
+            .enumerate()
+            .for_each(|(index, event)| {
+                if index > 0 {
+                    json.push(',');
+                }
+                json += &event;
+            });
+
+        json += "],\"displayTimeUnit\":\"ms\"}";
+        json
+    }
+}
+
+fn escape_json(text: &str) -> String {
+    text.chars()
+        .fold(String::with_capacity(text.len()), |mut acc, c| {
+            match c {
+                '"' => acc += "\\\"",
+                '\\' => acc += "\\\\",
+                c if c.is_control() => {
+                    let _ = write!(acc, "\\u{:04x}", c as u32);
+                }
+                c => acc.push(c),
+            }
+            acc
+        })
+}
+
+//====================================================================
+
+#[derive(Debug, Clone)]
+pub struct TimingStats {
+    samples: VecDeque<Duration>,
+    window: usize,
+}
+
+impl TimingStats {
+    pub fn new(window: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(window),
+            window,
+        }
+    }
+
+    pub fn push(&mut self, sample: Duration) {
+        if self.samples.len() >= self.window {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    #[inline]
+    pub fn sample_count(&self) -> usize {
+        self.samples.len()
+    }
+
+    #[inline]
+    pub fn latest(&self) -> Duration {
+        self.samples.back().copied().unwrap_or_default()
+    }
+
+    pub fn average(&self) -> Duration {
+        match self.samples.len() {
+            0 => Duration::ZERO,
+            count => self.samples.iter().sum::<Duration>() / count as u32,
+        }
+    }
+
+    #[inline]
+    pub fn min(&self) -> Duration {
+        self.samples.iter().min().copied().unwrap_or_default()
+    }
+
+    #[inline]
+    pub fn max(&self) -> Duration {
+        self.samples.iter().max().copied().unwrap_or_default()
+    }
+
+    // Nearest rank percentile. Percentile should be between 0 and 100.
+    pub fn percentile(&self, percentile: f32) -> Duration {
+        if self.samples.is_empty() {
+            return Duration::ZERO;
+        }
+
+        let mut sorted = self.samples.iter().copied().collect::<Vec<_>>();
+        sorted.sort();
+
+        let rank = (percentile.clamp(0., 100.) / 100. * sorted.len() as f32).ceil() as usize;
+        sorted[rank.saturating_sub(1).min(sorted.len() - 1)]
+    }
+}
+
+//====================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TraceCategory {
+    Frame,
+    Stage,
+    System,
+}
+
+impl TraceCategory {
+    fn as_str(&self) -> &'static str {
+        match self {
+            TraceCategory::Frame => "frame",
+            TraceCategory::Stage => "stage",
+            TraceCategory::System => "system",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct TraceEvent {
+    name: String,
+    category: TraceCategory,
+    thread: usize,
+    start: Duration, // Since the stats epoch
+    duration: Duration,
+}
+
+//--------------------------------------------------
+
+// Timings collected by the workload runner over a single frame
+pub(crate) struct FrameRecord {
+    pub start: Instant,
+    pub duration: Duration,
+    pub stages: Vec<TimingRecord>,
+    pub systems: Vec<TimingRecord>,
+}
+
+pub(crate) struct TimingRecord {
+    pub name: String,
+    pub thread: usize,
+    pub start: Instant,
+    pub duration: Duration,
+}
+
+impl FrameRecord {
+    // Returns None when profiling is disabled
+    pub fn begin(enabled: bool) -> Option<Self> {
+        #[cfg(feature = "profiling")]
+        system_spans::set_recording(enabled);
+
+        enabled.then(|| Self {
+            start: Instant::now(),
+            duration: Duration::ZERO,
+            stages: Vec::new(),
+            systems: Vec::new(),
+        })
+    }
+
+    pub fn record_stage(&mut self, name: String, start: Instant) {
+        self.stages.push(TimingRecord {
+            name,
+            thread: 0,
+            start,
+            duration: start.elapsed(),
+        });
+
+        #[cfg(feature = "profiling")]
+        self.systems.extend(system_spans::drain());
+    }
+
+    pub fn finish(mut self) -> Self {
+        self.duration = self.start.elapsed();
+        self
+    }
+}
+
+//====================================================================
+
+#[cfg(feature = "profiling")]
+pub use system_spans::{init_system_profiling, SystemSpanLayer};
+
+// Shipyard emits a tracing span for every system it runs when its 'tracing' feature is
+// enabled. This layer times those spans so they can be attributed to the stage that ran them.
+#[cfg(feature = "profiling")]
+mod system_spans {
+    use std::{
+        collections::HashMap,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Mutex, OnceLock,
+        },
+        thread::ThreadId,
+    };
+
+    use tracing::{span, Subscriber};
+    use tracing_subscriber::{layer::Context, registry::LookupSpan, Layer};
+    use web_time::Instant;
+
+    use super::TimingRecord;
+
+    // Only record spans while the runner is profiling, otherwise they would never be drained
+    static RECORDING: AtomicBool = AtomicBool::new(false);
+
+    #[derive(Default)]
+    struct Collector {
+        records: Vec<TimingRecord>,
+        threads: HashMap<ThreadId, usize>,
+    }
+
+    fn collector() -> &'static Mutex<Collector> {
+        static COLLECTOR: OnceLock<Mutex<Collector>> = OnceLock::new();
+        COLLECTOR.get_or_init(|| Mutex::new(Collector::default()))
+    }
+
+    pub(crate) fn set_recording(recording: bool) {
+        RECORDING.store(recording, Ordering::Relaxed);
+    }
+
+    pub(crate) fn drain() -> Vec<TimingRecord> {
+        match collector().lock() {
+            Ok(mut collector) => std::mem::take(&mut collector.records),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    // Installs the system span layer as the global tracing subscriber.
+    // Returns false if a global subscriber has already been set. In that case add
+    // 'SystemSpanLayer' to the existing subscriber instead.
+    pub fn init_system_profiling() -> bool {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let subscriber = tracing_subscriber::registry().with(SystemSpanLayer);
+        tracing::subscriber::set_global_default(subscriber).is_ok()
+    }
+
+    //--------------------------------------------------
+
+    #[derive(Default)]
+    pub struct SystemSpanLayer;
+
+    struct SystemSpan {
+        name: String,
+        entered: Option<Instant>,
+    }
+
+    struct NameVisitor(Option<String>);
+
+    impl tracing::field::Visit for NameVisitor {
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            if field.name() == "name" {
+                let name = format!("{:?}", value);
+                self.0 = Some(name.trim_matches('"').to_string());
+            }
+        }
+    }
+
+    impl<S> Layer<S> for SystemSpanLayer
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+            let metadata = attrs.metadata();
+            if metadata.name() != "system" || !metadata.target().starts_with("shipyard") {
+                return;
+            }
+
+            let mut visitor = NameVisitor(None);
+            attrs.record(&mut visitor);
+
+            if let (Some(name), Some(span)) = (visitor.0, ctx.span(id)) {
+                span.extensions_mut().insert(SystemSpan {
+                    name,
+                    entered: None,
+                });
+            }
+        }
+
+        fn on_enter(&self, id: &span::Id, ctx: Context<'_, S>) {
+            if !RECORDING.load(Ordering::Relaxed) {
+                return;
+            }
+
+            if let Some(span) = ctx.span(id) {
+                if let Some(system) = span.extensions_mut().get_mut::<SystemSpan>() {
+                    system.entered = Some(Instant::now());
+                }
+            }
+        }
+
+        fn on_exit(&self, id: &span::Id, ctx: Context<'_, S>) {
+            let Some(span) = ctx.span(id) else {
+                return;
+            };
+
+            let mut extensions = span.extensions_mut();
+            let Some(system) = extensions.get_mut::<SystemSpan>() else {
+                return;
+            };
+            let Some(start) = system.entered.take() else {
+                return;
+            };
+
+            let duration = start.elapsed();
+
+            if let Ok(mut collector) = collector().lock() {
+                let thread_count = collector.threads.len();
+                let thread = *collector
+                    .threads
+                    .entry(std::thread::current().id())
+                    .or_insert(thread_count + 1);
+
+                collector.records.push(TimingRecord {
+                    name: system.name.clone(),
+                    thread,
+                    start,
+                    duration,
+                });
+            }
+        }
+    }
+}
+
+//====================================================================
@@ -1,55 +1,132 @@
 //====================================================================
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use shipyard::World;
+use shipyard::{error::RunWorkload, World};
+use web_time::Instant;
 
-use crate::builder::{Label, StageData};
+use crate::{
+    builder::{Label, StageData, StageOrder, UniqueRequirement},
+    error::{BuildError, ErrorPolicy, FeatheredError},
+    profiling::{FrameRecord, FrameStats},
+    Res, ResMut,
+};
 
 //====================================================================
 
 pub struct WorkloadRunner {
     stages: HashMap<Label, StageData>,
+    setup_order: Vec<Label>,
     stage_order: Vec<Label>,
+    unique_requirements: Vec<UniqueRequirement>,
+
+    error_policy: ErrorPolicy,
+    failed_stages: HashSet<Label>,
 }
 
 impl WorkloadRunner {
-    pub fn new(stages: HashMap<Label, StageData>) -> Self {
-        let mut stage_data = stages
-            .iter()
-            .filter_map(|(label, data)| match data.priority != 0 {
-                true => Some((label, data)),
-                false => None,
+    // Stages should be provided in the order they were registered
+    pub fn new(stages: Vec<(Label, StageData)>) -> Result<Self, FeatheredError> {
+        let (setup_order, stage_order) = resolve_stage_order(
+            &stages
+                .iter()
+                .map(|(label, data)| (label.clone(), &data.order))
+                .collect::<Vec<_>>(),
+        )
+        .map_err(|unplaced| {
+            FeatheredError::Build(BuildError {
+                stages: unplaced,
+                ..Default::default()
             })
-            .collect::<Vec<_>>();
+        })?;
 
-        stage_data.sort_by(|a, b| a.1.priority.cmp(&b.1.priority));
-        let stage_order = stage_data
-            .into_iter()
-            .map(|(label, _)| label.clone())
-            .collect();
+        Ok(Self::from_order(stages, setup_order, stage_order))
+    }
+
+    pub(crate) fn from_order(
+        stages: Vec<(Label, StageData)>,
+        setup_order: Vec<Label>,
+        stage_order: Vec<Label>,
+    ) -> Self {
+        log::trace!("Stage order: {:?} -> {:?}", setup_order, stage_order);
 
         Self {
-            stages,
+            stages: stages.into_iter().collect(),
+            setup_order,
             stage_order,
+            unique_requirements: Vec::new(),
+            error_policy: ErrorPolicy::default(),
+            failed_stages: HashSet::new(),
         }
     }
 
-    pub fn prep(&self, world: &World) {
-        self.stages.iter().for_each(|(stage, data)| {
-            if data.priority == 0 {
-                log::info!("Running setup system {:?}", stage);
-                world.run_workload(stage.clone()).unwrap()
+    #[inline]
+    pub(crate) fn with_unique_requirements(mut self, requirements: Vec<UniqueRequirement>) -> Self {
+        self.unique_requirements = requirements;
+        self
+    }
+
+    #[inline]
+    pub fn with_error_policy(mut self, policy: ErrorPolicy) -> Self {
+        self.error_policy = policy;
+        self
+    }
+
+    #[inline]
+    pub fn error_policy(&self) -> ErrorPolicy {
+        self.error_policy
+    }
+
+    #[inline]
+    pub fn set_error_policy(&mut self, policy: ErrorPolicy) {
+        self.error_policy = policy;
+    }
+
+    pub fn prep(&mut self, world: &World) -> Result<(), FeatheredError> {
+        let missing_uniques = self
+            .unique_requirements
+            .iter()
+            .filter(|requirement| !(requirement.check)(world))
+            .map(|requirement| {
+                format!(
+                    "Plugin '{}' requires unique '{}' which hasn't been inserted",
+                    requirement.plugin, requirement.unique
+                )
+            })
+            .collect::<Vec<_>>();
+
+        if !missing_uniques.is_empty() {
+            missing_uniques
+                .iter()
+                .for_each(|missing| log::error!("{}", missing));
+            return Err(FeatheredError::MissingUniques(missing_uniques));
+        }
+
+        for stage in &self.setup_order {
+            log::info!("Running setup system {:?}", stage);
+
+            if let Err(error) = world.run_workload(stage.clone()) {
+                handle_error(self.error_policy, &mut self.failed_stages, stage, error)?;
             }
-        });
+        }
+
+        Ok(())
     }
 
-    pub fn run(&self, world: &World) {
-        self.stage_order.iter().for_each(|stage| {
+    // Only returns an error when using the 'Stop' error policy
+    pub fn run(&mut self, world: &World) -> Result<(), FeatheredError> {
+        let profiling = world
+            .borrow::<Res<FrameStats>>()
+            .map(|stats| stats.enabled())
+            .unwrap_or(false);
+
+        let mut record = FrameRecord::begin(profiling);
+
+        for stage in &self.stage_order {
             let data = self.stages.get(stage).unwrap();
 
             if data.disabled {
-                return;
+                continue;
             }
 
             let run = match &data.run_condition {
@@ -58,12 +135,179 @@ impl WorkloadRunner {
             };
 
             if !run {
-                return;
+                continue;
+            }
+
+            // Only timed when profiling
+            let start = record.as_ref().map(|_| Instant::now());
+
+            match &data.loop_condition {
+                Some(loop_condition) => {
+                    while loop_condition(world) {
+                        if let Err(error) = world.run_workload(stage.clone()) {
+                            handle_error(self.error_policy, &mut self.failed_stages, stage, error)?;
+                            break;
+                        }
+                    }
+                }
+                None => {
+                    if let Err(error) = world.run_workload(stage.clone()) {
+                        handle_error(self.error_policy, &mut self.failed_stages, stage, error)?;
+                    }
+                }
             }
 
-            world.run_workload(stage.clone()).unwrap();
+            if let (Some(record), Some(start)) = (&mut record, start) {
+                record.record_stage(format!("{:?}", stage), start);
+            }
+        }
+
+        if let Some(record) = record {
+            if let Ok(mut stats) = world.borrow::<ResMut<FrameStats>>() {
+                stats.record_frame(record.finish());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn handle_error(
+    policy: ErrorPolicy,
+    failed_stages: &mut HashSet<Label>,
+    stage: &Label,
+    error: RunWorkload,
+) -> Result<(), FeatheredError> {
+    let error = FeatheredError::Stage {
+        stage: format!("{:?}", stage),
+        error: Box::new(error),
+    };
+
+    match policy {
+        ErrorPolicy::LogAndSkip => {
+            // Avoid flooding the log with the same failure every frame
+            if failed_stages.insert(stage.clone()) {
+                log::error!("{}. Further errors from this stage won't be logged", error);
+            }
+            Ok(())
+        }
+        ErrorPolicy::Stop => {
+            log::error!("{}. Stopping", error);
+            Err(error)
+        }
+        ErrorPolicy::Panic => panic!("{}", error),
+    }
+}
+
+//====================================================================
+
+// Returns the setup stage order and the main stage order.
+// Priority stages are sorted first, then relative stages are placed next to the stage
+// they reference (which can also be a relative stage) in the order they were registered.
+// Fails with every relative stage that references a stage that isn't registered.
+pub(crate) fn resolve_stage_order(
+    stages: &[(Label, &StageOrder)],
+) -> Result<(Vec<Label>, Vec<Label>), Vec<String>> {
+    let mut priority_stages = stages
+        .iter()
+        .filter_map(|(label, order)| match order {
+            StageOrder::Priority(priority) => Some((*priority, label.clone())),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    priority_stages.sort_by_key(|(priority, _)| *priority);
+
+    let (setup, main): (Vec<_>, Vec<_>) = priority_stages
+        .into_iter()
+        .partition(|(priority, _)| *priority == 0);
+
+    let mut setup_order = setup
+        .into_iter()
+        .map(|(_, label)| label)
+        .collect::<Vec<_>>();
+    let mut stage_order = main.into_iter().map(|(_, label)| label).collect::<Vec<_>>();
+
+    let mut pending = stages
+        .iter()
+        .filter(|(_, order)| !matches!(order, StageOrder::Priority(_)))
+        .collect::<Vec<_>>();
+
+    // The stage each relative stage was placed next to
+    let mut anchors = HashMap::<Label, Label>::new();
+
+    // Is 'stage' placed relative to 'anchor', directly or through other relative stages
+    let attached_to = |anchors: &HashMap<Label, Label>, stage: &Label, anchor: &Label| {
+        let mut current = stage;
+        while let Some(next) = anchors.get(current) {
+            if next == anchor {
+                return true;
+            }
+            current = next;
+        }
+        false
+    };
+
+    loop {
+        let pending_count = pending.len();
+
+        pending.retain(|(label, order)| {
+            let (anchor, after) = match order {
+                StageOrder::Before(anchor) => (anchor, false),
+                StageOrder::After(anchor) => (anchor, true),
+                StageOrder::Priority(_) => unreachable!(),
+            };
+
+            let order = match (
+                setup_order.iter().position(|stage| stage == anchor),
+                stage_order.iter().position(|stage| stage == anchor),
+            ) {
+                (Some(index), _) => Some((&mut setup_order, index)),
+                (None, Some(index)) => Some((&mut stage_order, index)),
+                (None, None) => None,
+            };
+
+            let Some((order, index)) = order else {
+                return true;
+            };
+
+            match after {
+                // Goes after every stage already placed relative to the anchor
+                true => {
+                    let index = index
+                        + 1
+                        + order[index + 1..]
+                            .iter()
+                            .take_while(|stage| attached_to(&anchors, stage, anchor))
+                            .count();
+                    order.insert(index, label.clone());
+                }
+                false => order.insert(index, label.clone()),
+            }
+
+            anchors.insert(label.clone(), anchor.clone());
+
+            false
         });
+
+        if pending.is_empty() || pending.len() == pending_count {
+            break;
+        }
     }
+
+    if !pending.is_empty() {
+        return Err(pending
+            .into_iter()
+            .map(|(label, order)| {
+                format!(
+                    "Unable to place stage '{:?}' - {:?} references a stage that isn't registered",
+                    label, order
+                )
+            })
+            .collect());
+    }
+
+    Ok((setup_order, stage_order))
 }
 
 //====================================================================
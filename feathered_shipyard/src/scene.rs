@@ -0,0 +1,831 @@
+//====================================================================
+
+use std::{
+    any::Any,
+    collections::{BTreeMap, HashMap, HashSet},
+    error::Error,
+    fmt::Display,
+};
+
+use ron::error::SpannedError;
+use serde::{
+    de::{
+        DeserializeOwned, DeserializeSeed, Error as _, IgnoredAny, MapAccess, SeqAccess, Visitor,
+    },
+    ser::SerializeStruct,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use shipyard::{
+    AddComponent, AllStorages, Component, EntitiesViewMut, EntityId, Get, IntoIter, IntoWithId,
+    Unique, View, ViewMut,
+};
+
+use crate::{builder::WorkloadBuilder, Res, ResMut};
+
+//====================================================================
+
+// Components opt into scenes by describing how they are stored in a scene file.
+// 'Data' can be the component itself, or a stand in for components holding entities or assets.
+pub trait SceneComponent: Component + Send + Sync + Sized {
+    // Key used for the component in scene files
+    const NAME: &'static str;
+
+    type Data: Serialize + DeserializeOwned + Clone + Send + Sync + 'static;
+
+    // Returning None leaves the component out of the scene
+    fn save(&self, all_storages: &AllStorages) -> Option<Self::Data>;
+
+    // Returning None skips the component (with a warning)
+    fn load(data: Self::Data, entities: &EntityMap, all_storages: &AllStorages) -> Option<Self>;
+}
+
+pub trait SceneBuilder {
+    fn register_scene_component<C: SceneComponent>(&mut self) -> &mut Self;
+}
+
+impl SceneBuilder for WorkloadBuilder<'_> {
+    fn register_scene_component<C: SceneComponent>(&mut self) -> &mut Self {
+        self.get_inner().log(format!(
+            "Registering scene component '{}' - '{}'",
+            C::NAME,
+            std::any::type_name::<C>()
+        ));
+
+        if self.get_world().borrow::<Res<SceneRegistry>>().is_err() {
+            self.insert(SceneRegistry::default());
+        }
+
+        self.get_world()
+            .borrow::<ResMut<SceneRegistry>>()
+            .unwrap()
+            .register::<C>();
+
+        self
+    }
+}
+
+//====================================================================
+
+// Entities are referenced in scene files by the id they had when saved
+#[inline]
+pub fn scene_id(entity: EntityId) -> u64 {
+    entity.inner()
+}
+
+// Maps ids from a scene file to the entities spawned for them
+#[derive(Debug, Default, Clone)]
+pub struct EntityMap(HashMap<u64, EntityId>);
+
+impl EntityMap {
+    #[inline]
+    pub fn get(&self, id: u64) -> Option<EntityId> {
+        self.0.get(&id).copied()
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (u64, EntityId)> + '_ {
+        self.0.iter().map(|(id, entity)| (*id, *entity))
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+//====================================================================
+
+type Data = Box<dyn Any + Send + Sync>;
+
+#[derive(Clone, Copy)]
+struct Registration {
+    name: &'static str,
+    type_name: &'static str,
+    entities: fn(&AllStorages) -> Vec<EntityId>,
+    save: fn(&AllStorages, EntityId) -> Option<Data>,
+    clone: fn(&Data) -> Data,
+    insert: fn(&AllStorages, EntityId, Data, &EntityMap),
+
+    // Shared by every scene format
+    serialize: fn(&Data) -> &dyn erased_serde::Serialize,
+    deserialize: fn(&mut dyn erased_serde::Deserializer) -> Result<Data, erased_serde::Error>,
+}
+
+#[derive(Unique, Default)]
+pub struct SceneRegistry {
+    components: BTreeMap<&'static str, Registration>,
+}
+
+impl SceneRegistry {
+    pub fn register<C: SceneComponent>(&mut self) {
+        let registration = Registration {
+            name: C::NAME,
+            type_name: std::any::type_name::<C>(),
+            entities: component_entities::<C>,
+            save: save_component::<C>,
+            clone: |data| Box::new(downcast::<C>(data).clone()),
+            insert: insert_component::<C>,
+
+            serialize: |data| downcast::<C>(data),
+            deserialize: |deserializer| {
+                Ok(Box::new(erased_serde::deserialize::<C::Data>(
+                    deserializer,
+                )?))
+            },
+        };
+
+        if let Some(old) = self.components.insert(C::NAME, registration) {
+            if old.type_name != registration.type_name {
+                log::warn!(
+                    "Scene component '{}' registered by both '{}' and '{}'",
+                    C::NAME,
+                    old.type_name,
+                    registration.type_name
+                );
+            }
+        }
+    }
+
+    #[inline]
+    pub fn is_registered(&self, name: &str) -> bool {
+        self.components.contains_key(name)
+    }
+
+    #[inline]
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.components.keys().copied()
+    }
+}
+
+#[inline]
+fn downcast<C: SceneComponent>(data: &Data) -> &C::Data {
+    data.downcast_ref::<C::Data>().unwrap()
+}
+
+fn component_entities<C: SceneComponent>(all_storages: &AllStorages) -> Vec<EntityId> {
+    all_storages
+        .borrow::<View<C>>()
+        .map(|view| view.iter().with_id().map(|(id, _)| id).collect())
+        .unwrap_or_default()
+}
+
+fn save_component<C: SceneComponent>(all_storages: &AllStorages, entity: EntityId) -> Option<Data> {
+    let view = all_storages.borrow::<View<C>>().ok()?;
+    let data = view.get(entity).ok()?.save(all_storages)?;
+
+    Some(Box::new(data))
+}
+
+fn insert_component<C: SceneComponent>(
+    all_storages: &AllStorages,
+    entity: EntityId,
+    data: Data,
+    entities: &EntityMap,
+) {
+    let data = *data.downcast::<C::Data>().unwrap();
+
+    match C::load(data, entities, all_storages) {
+        Some(component) => all_storages
+            .borrow::<ViewMut<C>>()
+            .unwrap()
+            .add_component_unchecked(entity, component),
+
+        None => log::warn!(
+            "Unable to load scene component '{}' for {:?}",
+            C::NAME,
+            entity
+        ),
+    }
+}
+
+//====================================================================
+
+// Saved data of a registered component. Kept as the component's 'Data' type and
+// serialized directly to each scene format.
+pub struct SceneData {
+    data: Data,
+    registration: Registration,
+}
+
+impl SceneData {
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        self.registration.name
+    }
+
+    #[inline]
+    pub fn get<C: SceneComponent>(&self) -> Option<&C::Data> {
+        self.data.downcast_ref()
+    }
+}
+
+impl Clone for SceneData {
+    fn clone(&self) -> Self {
+        Self {
+            data: (self.registration.clone)(&self.data),
+            registration: self.registration,
+        }
+    }
+}
+
+impl std::fmt::Debug for SceneData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SceneData")
+            .field("component", &self.registration.type_name)
+            .finish()
+    }
+}
+
+//--------------------------------------------------
+
+#[derive(Debug, Clone, Default)]
+pub struct Scene {
+    pub entities: Vec<SceneEntity>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SceneEntity {
+    pub id: u64,
+    pub components: BTreeMap<String, SceneData>,
+}
+
+impl Scene {
+    // Save every entity with at least one registered component
+    pub fn from_world(all_storages: &AllStorages) -> Result<Self, SceneError> {
+        let registry = all_storages
+            .borrow::<Res<SceneRegistry>>()
+            .map_err(|_| SceneError::MissingRegistry)?;
+
+        let entities = registry
+            .components
+            .values()
+            .flat_map(|registration| (registration.entities)(all_storages))
+            .collect::<Vec<_>>();
+
+        Ok(Self::save(all_storages, &registry, entities))
+    }
+
+    // Save the registered components of the given entities. Entities referenced by these
+    // entities but not included won't be resolved when loading.
+    pub fn from_entities(
+        all_storages: &AllStorages,
+        entities: impl IntoIterator<Item = EntityId>,
+    ) -> Result<Self, SceneError> {
+        let registry = all_storages
+            .borrow::<Res<SceneRegistry>>()
+            .map_err(|_| SceneError::MissingRegistry)?;
+
+        Ok(Self::save(all_storages, &registry, entities))
+    }
+
+    fn save(
+        all_storages: &AllStorages,
+        registry: &SceneRegistry,
+        entities: impl IntoIterator<Item = EntityId>,
+    ) -> Self {
+        let mut saved = BTreeMap::new();
+
+        for entity in entities {
+            let id = scene_id(entity);
+            if saved.contains_key(&id) {
+                continue;
+            }
+
+            let components = registry
+                .components
+                .iter()
+                .filter_map(|(name, registration)| {
+                    let data = (registration.save)(all_storages, entity)?;
+                    Some((
+                        name.to_string(),
+                        SceneData {
+                            data,
+                            registration: *registration,
+                        },
+                    ))
+                })
+                .collect::<BTreeMap<_, _>>();
+
+            if !components.is_empty() {
+                saved.insert(id, SceneEntity { id, components });
+            }
+        }
+
+        Self {
+            entities: saved.into_values().collect(),
+        }
+    }
+
+    // Spawn new entities for the scene, with any entity references remapped to them
+    pub fn spawn(&self, all_storages: &AllStorages) -> Result<EntityMap, SceneError> {
+        // Check everything first so a bad scene doesn't leave a half loaded scene
+        let mut ids = HashSet::new();
+
+        if let Some(entity) = self.entities.iter().find(|entity| !ids.insert(entity.id)) {
+            return Err(SceneError::DuplicateEntity(entity.id));
+        }
+
+        let entities = {
+            let mut entities = all_storages.borrow::<EntitiesViewMut>().unwrap();
+
+            EntityMap(
+                self.entities
+                    .iter()
+                    .map(|entity| (entity.id, entities.add_entity((), ())))
+                    .collect(),
+            )
+        };
+
+        self.entities.iter().for_each(|scene_entity| {
+            let entity = entities.get(scene_entity.id).unwrap();
+
+            scene_entity.components.values().for_each(|data| {
+                let registration = data.registration;
+                (registration.insert)(
+                    all_storages,
+                    entity,
+                    (registration.clone)(&data.data),
+                    &entities,
+                );
+            });
+        });
+
+        Ok(entities)
+    }
+}
+
+//====================================================================
+
+// Scenes are written with serde, with each component serialized as its own type
+impl Scene {
+    pub fn to_ron(&self) -> Result<String, SceneError> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(SceneError::RonWrite)
+    }
+
+    // Components that aren't registered are skipped
+    pub fn from_ron(registry: &SceneRegistry, data: &str) -> Result<Self, SceneError> {
+        ron::Options::default()
+            .from_str_seed(data, registry)
+            .map_err(SceneError::Ron)
+    }
+
+    pub fn to_json(&self) -> Result<String, SceneError> {
+        serde_json::to_string_pretty(self).map_err(SceneError::Json)
+    }
+
+    // Components that aren't registered are skipped
+    pub fn from_json(registry: &SceneRegistry, data: &str) -> Result<Self, SceneError> {
+        let mut deserializer = serde_json::Deserializer::from_str(data);
+
+        let scene = registry
+            .deserialize(&mut deserializer)
+            .map_err(SceneError::Json)?;
+        deserializer.end().map_err(SceneError::Json)?;
+
+        Ok(scene)
+    }
+}
+
+impl Serialize for Scene {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut scene = serializer.serialize_struct("Scene", 1)?;
+        scene.serialize_field("entities", &self.entities)?;
+        scene.end()
+    }
+}
+
+impl Serialize for SceneEntity {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut entity = serializer.serialize_struct("SceneEntity", 2)?;
+        entity.serialize_field("id", &self.id)?;
+        entity.serialize_field("components", &self.components)?;
+        entity.end()
+    }
+}
+
+impl Serialize for SceneData {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        erased_serde::serialize((self.registration.serialize)(&self.data), serializer)
+    }
+}
+
+//--------------------------------------------------
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "lowercase")]
+enum SceneField {
+    Entities,
+}
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "lowercase")]
+enum EntityField {
+    Id,
+    Components,
+}
+
+// Scenes are read through the registry, which looks up each component by name
+// and deserializes it as the registered type
+impl<'de> DeserializeSeed<'de> for &SceneRegistry {
+    type Value = Scene;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Scene, D::Error> {
+        deserializer.deserialize_struct("Scene", &["entities"], SceneVisitor(self))
+    }
+}
+
+struct SceneVisitor<'a>(&'a SceneRegistry);
+
+impl<'de> Visitor<'de> for SceneVisitor<'_> {
+    type Value = Scene;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "a scene")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Scene, A::Error> {
+        let mut entities = None;
+
+        while let Some(field) = map.next_key()? {
+            match field {
+                SceneField::Entities if entities.is_some() => {
+                    return Err(A::Error::duplicate_field("entities"))
+                }
+                SceneField::Entities => entities = Some(map.next_value_seed(EntitiesSeed(self.0))?),
+            }
+        }
+
+        Ok(Scene {
+            entities: entities.ok_or_else(|| A::Error::missing_field("entities"))?,
+        })
+    }
+}
+
+struct EntitiesSeed<'a>(&'a SceneRegistry);
+
+impl<'de> DeserializeSeed<'de> for EntitiesSeed<'_> {
+    type Value = Vec<SceneEntity>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for EntitiesSeed<'_> {
+    type Value = Vec<SceneEntity>;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "a list of scene entities")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut entities = Vec::new();
+
+        while let Some(entity) = seq.next_element_seed(EntitySeed(self.0))? {
+            entities.push(entity);
+        }
+
+        Ok(entities)
+    }
+}
+
+struct EntitySeed<'a>(&'a SceneRegistry);
+
+impl<'de> DeserializeSeed<'de> for EntitySeed<'_> {
+    type Value = SceneEntity;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_struct("SceneEntity", &["id", "components"], self)
+    }
+}
+
+impl<'de> Visitor<'de> for EntitySeed<'_> {
+    type Value = SceneEntity;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "a scene entity")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut id = None;
+        let mut components = None;
+
+        while let Some(field) = map.next_key()? {
+            match field {
+                EntityField::Id if id.is_some() => return Err(A::Error::duplicate_field("id")),
+                EntityField::Id => id = Some(map.next_value()?),
+
+                EntityField::Components if components.is_some() => {
+                    return Err(A::Error::duplicate_field("components"))
+                }
+                EntityField::Components => {
+                    components = Some(map.next_value_seed(ComponentsSeed {
+                        registry: self.0,
+                        entity: id,
+                    })?)
+                }
+            }
+        }
+
+        Ok(SceneEntity {
+            id: id.ok_or_else(|| A::Error::missing_field("id"))?,
+            components: components.ok_or_else(|| A::Error::missing_field("components"))?,
+        })
+    }
+}
+
+// The entity id is only known here if it came before the components
+struct ComponentsSeed<'a> {
+    registry: &'a SceneRegistry,
+    entity: Option<u64>,
+}
+
+impl<'de> DeserializeSeed<'de> for ComponentsSeed<'_> {
+    type Value = BTreeMap<String, SceneData>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ComponentsSeed<'_> {
+    type Value = BTreeMap<String, SceneData>;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "a map of scene components")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut components = BTreeMap::new();
+
+        while let Some(name) = map.next_key::<String>()? {
+            match self.registry.components.get(name.as_str()) {
+                Some(registration) => {
+                    let data = map.next_value_seed(ComponentSeed {
+                        registration,
+                        entity: self.entity,
+                    })?;
+                    components.insert(name, data);
+                }
+                None => {
+                    log::warn!(
+                        "Skipping unregistered scene component '{}' on entity {:?}",
+                        name,
+                        self.entity
+                    );
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        Ok(components)
+    }
+}
+
+struct ComponentSeed<'a> {
+    registration: &'a Registration,
+    entity: Option<u64>,
+}
+
+impl<'de> DeserializeSeed<'de> for ComponentSeed<'_> {
+    type Value = SceneData;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        let mut deserializer = <dyn erased_serde::Deserializer>::erase(deserializer);
+
+        let data = (self.registration.deserialize)(&mut deserializer).map_err(|error| {
+            D::Error::custom(match self.entity {
+                Some(entity) => format!(
+                    "Invalid scene component '{}' on entity {} - {}",
+                    self.registration.name, entity, error
+                ),
+                None => format!(
+                    "Invalid scene component '{}' - {}",
+                    self.registration.name, error
+                ),
+            })
+        })?;
+
+        Ok(SceneData {
+            data,
+            registration: *self.registration,
+        })
+    }
+}
+
+//====================================================================
+
+#[derive(Debug)]
+pub enum SceneError {
+    MissingRegistry,
+    DuplicateEntity(u64),
+    Ron(SpannedError),
+    RonWrite(ron::Error),
+    Json(serde_json::Error),
+}
+
+impl Error for SceneError {}
+
+impl Display for SceneError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SceneError::MissingRegistry => write!(f, "No scene components have been registered"),
+            SceneError::DuplicateEntity(id) => {
+                write!(f, "Entity {} appears more than once in the scene", id)
+            }
+            SceneError::Ron(error) => write!(f, "Invalid RON scene - {}", error),
+            SceneError::RonWrite(error) => write!(f, "Unable to write RON scene - {}", error),
+            SceneError::Json(error) => write!(f, "Invalid JSON scene - {}", error),
+        }
+    }
+}
+
+//====================================================================
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use shipyard::{AllStorages, AllStoragesView, Component, IntoIter, View, World};
+
+    use super::{EntityMap, Scene, SceneComponent, SceneError, SceneRegistry};
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    enum Shape {
+        Point,
+        Circle(f32),
+        Rect { width: f32, height: f32 },
+    }
+
+    #[derive(Component, Serialize, Deserialize, Debug, Clone, PartialEq)]
+    struct Body {
+        mass: f32,
+        shapes: Vec<Shape>,
+        parent: Option<u64>,
+    }
+
+    impl SceneComponent for Body {
+        const NAME: &'static str = "Body";
+        type Data = Self;
+
+        fn save(&self, _: &AllStorages) -> Option<Self::Data> {
+            Some(self.clone())
+        }
+
+        fn load(data: Self::Data, _: &EntityMap, _: &AllStorages) -> Option<Self> {
+            Some(data)
+        }
+    }
+
+    fn body() -> Body {
+        Body {
+            mass: 0.1,
+            shapes: vec![
+                Shape::Point,
+                Shape::Circle(0.3),
+                Shape::Rect {
+                    width: 1.5,
+                    height: 0.2,
+                },
+            ],
+            parent: None,
+        }
+    }
+
+    fn world() -> World {
+        let world = World::new();
+        let mut registry = SceneRegistry::default();
+        registry.register::<Body>();
+        world.add_unique(registry);
+        world
+    }
+
+    fn save(world: &mut World) -> Scene {
+        world.add_entity(body());
+        Scene::from_world(&world.borrow::<AllStoragesView>().unwrap()).unwrap()
+    }
+
+    fn spawned_bodies(world: &World, scene: &Scene) -> Vec<Body> {
+        scene
+            .spawn(&world.borrow::<AllStoragesView>().unwrap())
+            .unwrap();
+        world.run(|v_body: View<Body>| v_body.iter().cloned().collect())
+    }
+
+    #[test]
+    fn ron_keeps_component_types() {
+        let mut world = world();
+        let ron = save(&mut world).to_ron().unwrap();
+
+        // Written as the component's own type rather than a string keyed map of f64s
+        assert!(ron.contains("mass: 0.1,"), "{}", ron);
+        assert!(ron.contains("Circle(0.3)"), "{}", ron);
+        assert!(!ron.contains("\"mass\""), "{}", ron);
+
+        let registry = world.borrow::<super::Res<SceneRegistry>>().unwrap();
+        let scene = Scene::from_ron(&registry, &ron).unwrap();
+        drop(registry);
+
+        assert_eq!(spawned_bodies(&world, &scene), vec![body(), body()]);
+    }
+
+    #[test]
+    fn json_keeps_component_types() {
+        let mut world = world();
+        let json = save(&mut world).to_json().unwrap();
+
+        assert!(json.contains("\"mass\": 0.1,"), "{}", json);
+
+        let registry = world.borrow::<super::Res<SceneRegistry>>().unwrap();
+        let scene = Scene::from_json(&registry, &json).unwrap();
+        drop(registry);
+
+        assert_eq!(spawned_bodies(&world, &scene), vec![body(), body()]);
+    }
+
+    #[test]
+    fn ron_errors_keep_their_position() {
+        let world = world();
+        let registry = world.borrow::<super::Res<SceneRegistry>>().unwrap();
+
+        let ron = "(\n    entities: [\n        (\n            id: 1,\n            components: {\n                \"Body\": (mass: \"heavy\", shapes: [], parent: None),\n            },\n        ),\n    ],\n)";
+
+        match Scene::from_ron(&registry, ron) {
+            Err(SceneError::Ron(error)) => {
+                assert_eq!(error.position.line, 6);
+                assert_eq!(error.position.col, 32);
+
+                let message = error.to_string();
+                assert!(message.contains("'Body' on entity 1"), "{}", message);
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+
+        match Scene::from_ron(&registry, "(\n    entites: [],\n)") {
+            Err(SceneError::Ron(error)) => assert_eq!(error.position.line, 2),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn unregistered_components_are_skipped() {
+        let world = world();
+        let registry = world.borrow::<super::Res<SceneRegistry>>().unwrap();
+
+        let ron = "(entities: [(id: 1, components: {\"Unknown\": Some((a: 1)), \"Body\": (mass: 1.0, shapes: [Point], parent: Some(2))})])";
+        let scene = Scene::from_ron(&registry, ron).unwrap();
+
+        assert_eq!(scene.entities.len(), 1);
+        assert_eq!(
+            scene.entities[0].components.keys().collect::<Vec<_>>(),
+            vec!["Body"]
+        );
+        assert_eq!(
+            scene.entities[0].components["Body"]
+                .get::<Body>()
+                .unwrap()
+                .parent,
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn ron_accepts_hand_written_scenes() {
+        let mut world = world();
+        let registry = world.borrow::<super::Res<SceneRegistry>>().unwrap();
+
+        // Named structs, extensions and fields in any order
+        let ron = "#![enable(implicit_some)]\n// Level 1\nScene(\n    entities: [\n        SceneEntity(\n            components: {\n                \"Body\": Body(shapes: [Circle(0.3)], parent: 2, mass: 1.0),\n            },\n            id: 1,\n        ),\n    ],\n)";
+        let scene = Scene::from_ron(&registry, ron).unwrap();
+
+        assert_eq!(scene.entities[0].id, 1);
+        assert_eq!(
+            scene.entities[0].components["Body"].get::<Body>(),
+            Some(&Body {
+                mass: 1.,
+                shapes: vec![Shape::Circle(0.3)],
+                parent: Some(2),
+            })
+        );
+        drop(registry);
+
+        // Other writer configs read back the same
+        let saved = save(&mut world);
+        let config = ron::ser::PrettyConfig::default()
+            .struct_names(true)
+            .compact_arrays(true);
+        let ron = ron::ser::to_string_pretty(&saved, config).unwrap();
+
+        let registry = world.borrow::<super::Res<SceneRegistry>>().unwrap();
+        let scene = Scene::from_ron(&registry, &ron).unwrap();
+        drop(registry);
+
+        assert_eq!(spawned_bodies(&world, &scene), vec![body(), body()]);
+    }
+}
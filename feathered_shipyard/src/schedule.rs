@@ -0,0 +1,472 @@
+//====================================================================
+
+use std::{cmp::Ordering, fmt::Display};
+
+use shipyard::{info::Conflict, AsLabel, Unique};
+
+use crate::builder::{Label, Stage, StageData, StageOrder, SubStages};
+
+//====================================================================
+
+// Snapshot of the built schedule. Inserted into the world when the workloads are built.
+#[derive(Unique, Debug, Clone, Default)]
+pub struct ScheduleInfo {
+    // Setup stages first, followed by the main stages, in the order they run
+    stages: Vec<StageInfo>,
+}
+
+#[derive(Debug, Clone)]
+pub struct StageInfo {
+    pub label: Label,
+    pub name: String,
+    pub order: StageOrder,
+    pub setup: bool,
+    pub run_condition: bool,
+    pub loop_condition: bool,
+    pub disabled: bool,
+    pub substages: Vec<SubStageInfo>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SubStageInfo {
+    pub substage: SubStages,
+    // Descriptions of conditions gating workloads in this substage (events, states, etc.)
+    pub conditions: Vec<String>,
+    pub batches: Vec<BatchInfo>,
+}
+
+// Systems in the same batch may run in parallel. Batch indices are counted across the whole stage.
+#[derive(Debug, Clone)]
+pub struct BatchInfo {
+    pub index: usize,
+    pub systems: Vec<SystemInfo>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SystemInfo {
+    pub name: String,
+    // Why this system couldn't be placed in the previous batch
+    pub conflict: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SystemLocation<'a> {
+    pub stage: &'a StageInfo,
+    pub substage: SubStages,
+    pub batch: usize,
+    pub system: &'a SystemInfo,
+}
+
+//====================================================================
+
+impl StageInfo {
+    pub(crate) fn new(
+        label: Label,
+        name: String,
+        data: &StageData,
+        substages: &[SubStages],
+        conditions: &[(SubStages, String)],
+        workload_info: Option<&shipyard::info::WorkloadInfo>,
+    ) -> Self {
+        let mut substages = substages
+            .iter()
+            .map(|substage| SubStageInfo {
+                substage: *substage,
+                conditions: conditions
+                    .iter()
+                    .filter(|(condition_substage, _)| condition_substage == substage)
+                    .map(|(_, condition)| condition.clone())
+                    .collect(),
+                batches: Vec::new(),
+            })
+            .collect::<Vec<_>>();
+
+        substages.sort_by_key(|substage| substage.substage as u8);
+
+        // Substage workloads are tagged and placed before all following substages, so the
+        // substage a system belongs to can be worked out from its 'before' requirements.
+        let substage_names = substages
+            .iter()
+            .map(|substage| format!("{:?}", substage.substage.as_label()))
+            .collect::<Vec<_>>();
+
+        if let Some(workload_info) = workload_info {
+            workload_info
+                .batch_info
+                .iter()
+                .enumerate()
+                .for_each(|(index, batch)| {
+                    batch.systems().for_each(|system| {
+                        let substage_index = substage_names
+                            .iter()
+                            .position(|name| system.before.contains(name))
+                            .unwrap_or(substage_names.len())
+                            .saturating_sub(1);
+
+                        let Some(substage) = substages.get_mut(substage_index) else {
+                            return;
+                        };
+
+                        let system = SystemInfo {
+                            name: system.name.clone(),
+                            conflict: system.conflict.as_ref().map(describe_conflict),
+                        };
+
+                        match substage.batches.last_mut() {
+                            Some(batch) if batch.index == index => batch.systems.push(system),
+                            _ => substage.batches.push(BatchInfo {
+                                index,
+                                systems: vec![system],
+                            }),
+                        }
+                    })
+                });
+        }
+
+        Self {
+            label,
+            name,
+            order: data.order.clone(),
+            setup: data.order == StageOrder::Priority(0),
+            run_condition: data.run_condition.is_some(),
+            loop_condition: data.loop_condition.is_some(),
+            disabled: data.disabled,
+            substages,
+        }
+    }
+
+    #[inline]
+    pub fn batch_count(&self) -> usize {
+        self.substages
+            .iter()
+            .flat_map(|substage| substage.batches.last())
+            .map(|batch| batch.index + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn systems(&self) -> impl Iterator<Item = SystemLocation<'_>> {
+        self.substages.iter().flat_map(move |substage| {
+            substage.batches.iter().flat_map(move |batch| {
+                batch.systems.iter().map(move |system| SystemLocation {
+                    stage: self,
+                    substage: substage.substage,
+                    batch: batch.index,
+                    system,
+                })
+            })
+        })
+    }
+}
+
+fn describe_conflict(conflict: &Conflict) -> String {
+    match conflict {
+        Conflict::Borrow {
+            other_system,
+            other_type_info,
+            ..
+        } => format!("{} borrows {}", other_system.name, other_type_info.name),
+        Conflict::NotSendSync(type_info) => format!("borrows !Send/!Sync {}", type_info.name),
+        Conflict::OtherNotSendSync { system, type_info } => {
+            format!("{} borrows !Send/!Sync {}", system.name, type_info.name)
+        }
+    }
+}
+
+//====================================================================
+
+impl ScheduleInfo {
+    #[inline]
+    pub(crate) fn new(stages: Vec<StageInfo>) -> Self {
+        Self { stages }
+    }
+
+    #[inline]
+    pub fn stages(&self) -> impl Iterator<Item = &StageInfo> {
+        self.stages.iter()
+    }
+
+    pub fn stage<S: Stage>(&self, stage: S) -> Option<&StageInfo> {
+        let label = stage.as_label();
+        self.stages.iter().find(|info| info.label.eq(&label))
+    }
+
+    pub fn systems(&self) -> impl Iterator<Item = SystemLocation<'_>> {
+        self.stages.iter().flat_map(|stage| stage.systems())
+    }
+
+    // Matches either the full system name or the name without its module path
+    pub fn find_system(&self, name: &str) -> Option<SystemLocation<'_>> {
+        let suffix = format!("::{}", name);
+
+        self.systems().find(|location| {
+            location.system.name == name || location.system.name.ends_with(&suffix)
+        })
+    }
+
+    // Equal if both systems are in the same batch and may run in parallel
+    pub fn system_order(&self, a: &str, b: &str) -> Option<Ordering> {
+        let position = |name: &str| {
+            let location = self.find_system(name)?;
+            let stage = self
+                .stages
+                .iter()
+                .position(|stage| std::ptr::eq(stage, location.stage))?;
+
+            Some((stage, location.batch))
+        };
+
+        Some(position(a)?.cmp(&position(b)?))
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from(
+            "digraph Schedule {\n    compound=true;\n    node [shape=box, fontname=\"monospace\"];\n",
+        );
+
+        // First and last node of each stage, used to connect the stages together
+        let mut stage_nodes = Vec::new();
+
+        self.stages
+            .iter()
+            .enumerate()
+            .for_each(|(stage_index, stage)| {
+                let mut flags = Vec::new();
+                if stage.setup {
+                    flags.push("setup");
+                }
+                if stage.run_condition {
+                    flags.push("run condition");
+                }
+                if stage.loop_condition {
+                    flags.push("loop condition");
+                }
+                if stage.disabled {
+                    flags.push("disabled");
+                }
+
+                let label = match flags.is_empty() {
+                    true => format!("{} - {:?}", stage.name, stage.order),
+                    false => format!(
+                        "{} - {:?} ({})",
+                        stage.name,
+                        stage.order,
+                        flags.join(", ")
+                    ),
+                };
+
+                dot += &format!(
+                    "    subgraph cluster_{} {{\n        label=\"{}\";\n",
+                    stage_index,
+                    escape(&label)
+                );
+
+                let mut nodes = Vec::new();
+
+                stage.substages.iter().for_each(|substage| {
+                    dot += &format!(
+                        "        subgraph cluster_{}_{:?} {{\n            label=\"{:?}\";\n            style=dashed;\n",
+                        stage_index, substage.substage, substage.substage
+                    );
+
+                    substage.conditions.iter().enumerate().for_each(|(index, condition)| {
+                        dot += &format!(
+                            "            \"s{}_{:?}_c{}\" [label=\"runs if: {}\", shape=note];\n",
+                            stage_index,
+                            substage.substage,
+                            index,
+                            escape(condition)
+                        );
+                    });
+
+                    substage.batches.iter().for_each(|batch| {
+                        let node = format!("s{}_b{}", stage_index, batch.index);
+
+                        let label = batch.systems.iter().fold(
+                            format!("batch {}\\l", batch.index),
+                            |acc, system| match &system.conflict {
+                                Some(conflict) => format!(
+                                    "{}{}\\l    (conflict: {})\\l",
+                                    acc,
+                                    escape(&system.name),
+                                    escape(conflict)
+                                ),
+                                None => format!("{}{}\\l", acc, escape(&system.name)),
+                            },
+                        );
+
+                        dot += &format!("            \"{}\" [label=\"{}\"];\n", node, label);
+                        nodes.push(node);
+                    });
+
+                    dot += "        }\n";
+                });
+
+                if nodes.is_empty() {
+                    let node = format!("s{}_empty", stage_index);
+                    dot += &format!("        \"{}\" [label=\"\", shape=point];\n", node);
+                    nodes.push(node);
+                }
+
+                dot += "    }\n";
+
+                nodes.windows(2).for_each(|pair| {
+                    dot += &format!("    \"{}\" -> \"{}\";\n", pair[0], pair[1]);
+                });
+
+                stage_nodes.push((
+                    stage_index,
+                    nodes.first().unwrap().clone(),
+                    nodes.last().unwrap().clone(),
+                ));
+            });
+
+        stage_nodes.windows(2).for_each(|pair| {
+            let (from_index, _, from) = &pair[0];
+            let (to_index, to, _) = &pair[1];
+
+            dot += &format!(
+                "    \"{}\" -> \"{}\" [ltail=cluster_{}, lhead=cluster_{}, style=bold];\n",
+                from, to, from_index, to_index
+            );
+        });
+
+        dot += "}\n";
+        dot
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+//--------------------------------------------------
+
+impl Display for ScheduleInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.stages.iter().try_for_each(|stage| {
+            writeln!(f, "{} - {:?}", stage.name, stage.order)?;
+
+            stage.substages.iter().try_for_each(|substage| {
+                writeln!(f, "    {:?}", substage.substage)?;
+
+                substage
+                    .conditions
+                    .iter()
+                    .try_for_each(|condition| writeln!(f, "        runs if: {}", condition))?;
+
+                substage.batches.iter().try_for_each(|batch| {
+                    writeln!(f, "        batch {}", batch.index)?;
+                    batch
+                        .systems
+                        .iter()
+                        .try_for_each(|system| writeln!(f, "            {}", system.name))
+                })
+            })
+        })
+    }
+}
+
+//====================================================================
+
+#[cfg(test)]
+mod tests {
+    use shipyard::{IntoWorkload, World};
+
+    use super::ScheduleInfo;
+    use crate::{
+        builder::{register_main_stages, SubStages, Update, WorkloadBuilder},
+        events::{Event, EventBuilder},
+        Res,
+    };
+
+    #[derive(Debug)]
+    struct Ping;
+    impl Event for Ping {}
+
+    fn sys_first() {}
+    fn sys_pre() {}
+    fn sys_main() {}
+    fn sys_post() {}
+    fn sys_on_ping() {}
+
+    fn schedule() -> ScheduleInfo {
+        let world = World::new();
+        let mut builder = WorkloadBuilder::new(&world);
+        register_main_stages(&mut builder);
+
+        builder
+            .register_event::<Ping>()
+            .add_workload_first(Update, sys_first)
+            .add_workload_pre(Update, sys_pre)
+            .add_workload(Update, sys_main)
+            .add_workload_post(Update, sys_post)
+            .event_workload::<Ping>(Update, sys_on_ping.into_workload());
+
+        builder.build().unwrap();
+
+        world.run(|schedule: Res<ScheduleInfo>| schedule.clone())
+    }
+
+    #[test]
+    fn systems_report_their_substage() {
+        let schedule = schedule();
+
+        [
+            ("sys_first", SubStages::First),
+            ("sys_pre", SubStages::Pre),
+            ("sys_main", SubStages::Main),
+            ("sys_post", SubStages::Post),
+            ("sys_on_ping", SubStages::Main),
+        ]
+        .into_iter()
+        .for_each(|(name, substage)| {
+            let location = schedule.find_system(name).unwrap();
+            assert_eq!(location.substage, substage, "{}", name);
+            assert_eq!(location.stage.name, "Update", "{}", name);
+        });
+
+        let main = schedule
+            .stage(Update)
+            .unwrap()
+            .substages
+            .iter()
+            .find(|substage| substage.substage == SubStages::Main)
+            .unwrap();
+        assert_eq!(main.conditions.len(), 1);
+        assert!(main.conditions[0].contains("Ping"));
+    }
+
+    #[test]
+    fn dot_output_groups_systems_by_substage() {
+        let schedule = schedule();
+        let dot = schedule.to_dot();
+
+        assert!(dot.starts_with("digraph Schedule {"));
+        assert!(dot.ends_with("}\n"));
+
+        let stage = schedule
+            .stages()
+            .position(|stage| stage.name == "Update")
+            .unwrap();
+
+        // Contents of a substage's cluster
+        let cluster = |substage: SubStages| {
+            let start = dot
+                .find(&format!("cluster_{}_{:?} {{", stage, substage))
+                .unwrap();
+            let end = start + dot[start..].find("\n        }\n").unwrap();
+            &dot[start..end]
+        };
+
+        assert!(cluster(SubStages::First).contains("sys_first"));
+        assert!(cluster(SubStages::Pre).contains("sys_pre"));
+        assert!(cluster(SubStages::Post).contains("sys_post"));
+
+        let main = cluster(SubStages::Main);
+        assert!(main.contains("sys_main"));
+        assert!(main.contains("sys_on_ping"));
+        assert!(main.contains("runs if: event"));
+        assert!(!main.contains("sys_pre"));
+    }
+}
@@ -0,0 +1,263 @@
+//====================================================================
+
+use std::{fmt::Debug, hash::Hash};
+
+use shipyard::{IntoWorkload, Unique, WorkloadModificator, World};
+
+use crate::{
+    builder::{stage_macros, First, Stage, StageData, SubStages, Update, WorkloadBuilder},
+    Res, ResMut,
+};
+
+//====================================================================
+
+pub trait State: 'static + Send + Sync + Debug + Clone + Hash + PartialEq + Eq {}
+impl<T> State for T where T: 'static + Send + Sync + Debug + Clone + Hash + PartialEq + Eq {}
+
+stage_macros::create_stage!(StateTransition);
+
+// Runs once when the state is entered
+#[derive(Debug, Clone)]
+pub struct OnEnter<S: State>(pub S);
+
+// Runs once when the state is exited
+#[derive(Debug, Clone)]
+pub struct OnExit<S: State>(pub S);
+
+// Runs every frame in the `Update` stage while in the state
+#[derive(Debug, Clone)]
+pub struct OnUpdate<S: State>(pub S);
+
+//====================================================================
+
+#[derive(Debug, Clone)]
+pub struct Transition<S: State> {
+    pub from: Option<S>,
+    pub to: S,
+}
+
+#[derive(Unique)]
+pub struct AppState<S: State> {
+    current: S,
+    queued: Option<S>,
+    transition: Option<Transition<S>>,
+    entered: bool,
+}
+
+impl<S: State> AppState<S> {
+    fn new(initial: S) -> Self {
+        Self {
+            current: initial,
+            queued: None,
+            transition: None,
+            entered: false,
+        }
+    }
+
+    #[inline]
+    pub fn current(&self) -> &S {
+        &self.current
+    }
+
+    #[inline]
+    pub fn queued(&self) -> Option<&S> {
+        self.queued.as_ref()
+    }
+
+    // Transition currently being applied. Only available during the `StateTransition` stage.
+    #[inline]
+    pub fn transition(&self) -> Option<&Transition<S>> {
+        self.transition.as_ref()
+    }
+
+    #[inline]
+    pub fn is(&self, state: &S) -> bool {
+        self.current == *state
+    }
+
+    // Queue a state change. Applied at the start of the next `StateTransition` stage.
+    #[inline]
+    pub fn set(&mut self, next: S) {
+        self.queued = Some(next);
+    }
+
+    fn exiting(&self, state: &S) -> bool {
+        matches!(&self.transition, Some(Transition { from: Some(from), .. }) if from == state)
+    }
+
+    fn entering(&self, state: &S) -> bool {
+        matches!(&self.transition, Some(Transition { to, .. }) if to == state)
+    }
+}
+
+//====================================================================
+
+pub trait StateWorkload<S: State> {
+    fn add_to_builder(self, builder: &mut WorkloadBuilder, workload: shipyard::Workload);
+}
+
+impl<S: State> StateWorkload<S> for OnEnter<S> {
+    fn add_to_builder(self, builder: &mut WorkloadBuilder, workload: shipyard::Workload) {
+        let state = self.0;
+        builder.get_inner().register_workload_condition(
+            &StateTransition,
+            SubStages::Post,
+            format!("entering state {:?}", state),
+        );
+        builder.get_inner().add_workload_sub(
+            StateTransition,
+            SubStages::Post,
+            workload.skip_if(move |app_state: Res<AppState<S>>| !app_state.entering(&state)),
+            true,
+        );
+    }
+}
+
+impl<S: State> StateWorkload<S> for OnExit<S> {
+    fn add_to_builder(self, builder: &mut WorkloadBuilder, workload: shipyard::Workload) {
+        let state = self.0;
+        builder.get_inner().register_workload_condition(
+            &StateTransition,
+            SubStages::Pre,
+            format!("exiting state {:?}", state),
+        );
+        builder.get_inner().add_workload_sub(
+            StateTransition,
+            SubStages::Pre,
+            workload.skip_if(move |app_state: Res<AppState<S>>| !app_state.exiting(&state)),
+            true,
+        );
+    }
+}
+
+impl<S: State> StateWorkload<S> for OnUpdate<S> {
+    fn add_to_builder(self, builder: &mut WorkloadBuilder, workload: shipyard::Workload) {
+        let state = self.0;
+        builder.get_inner().register_workload_condition(
+            &Update,
+            SubStages::Main,
+            format!("in state {:?}", state),
+        );
+        builder.get_inner().add_workload_sub(
+            Update,
+            SubStages::Main,
+            workload.skip_if(move |app_state: Res<AppState<S>>| !app_state.is(&state)),
+            true,
+        );
+    }
+}
+
+//--------------------------------------------------
+
+pub trait StateBuilder {
+    fn register_state<S: State>(&mut self, initial: S) -> &mut Self;
+
+    fn add_state_workload<S, W, Views, R, Sys>(&mut self, when: W, workload: Sys) -> &mut Self
+    where
+        S: State,
+        W: StateWorkload<S> + Debug,
+        Sys: IntoWorkload<Views, R>,
+        R: 'static;
+}
+
+impl StateBuilder for WorkloadBuilder<'_> {
+    fn register_state<S: State>(&mut self, initial: S) -> &mut Self {
+        self.get_inner().log(format!(
+            "Registering state type '{}' - initial state {:?}",
+            std::any::type_name::<S>(),
+            initial
+        ));
+
+        self.insert(AppState::new(initial));
+
+        if !self.contains_stage(StateTransition) {
+            // Transitions are applied after `First` and before any fixed or update stages
+            self.register_stage(StateTransition, StageData::after(First), None);
+        }
+
+        self.get_inner().add_workload_sub(
+            StateTransition,
+            SubStages::First,
+            sys_begin_transition::<S>.into_workload(),
+            true,
+        );
+        self.get_inner().add_workload_sub(
+            StateTransition,
+            SubStages::Main,
+            sys_apply_transition::<S>.into_workload(),
+            true,
+        );
+        self.get_inner().add_workload_sub(
+            StateTransition,
+            SubStages::Last,
+            sys_end_transition::<S>.into_workload(),
+            true,
+        );
+
+        self
+    }
+
+    fn add_state_workload<S, W, Views, R, Sys>(&mut self, when: W, workload: Sys) -> &mut Self
+    where
+        S: State,
+        W: StateWorkload<S> + Debug,
+        Sys: IntoWorkload<Views, R>,
+        R: 'static,
+    {
+        self.get_inner()
+            .log(format!("Adding state workload for '{:?}'", when));
+
+        when.add_to_builder(self, workload.into_workload());
+        self
+    }
+}
+
+//====================================================================
+
+fn sys_begin_transition<S: State>(mut app_state: ResMut<AppState<S>>) {
+    // Anything queued before the initial enter (such as during setup) is applied next frame
+    if !app_state.entered {
+        app_state.entered = true;
+        app_state.transition = Some(Transition {
+            from: None,
+            to: app_state.current.clone(),
+        });
+        return;
+    }
+
+    let next = match app_state.queued.take() {
+        Some(next) if next != app_state.current => next,
+        _ => return,
+    };
+
+    log::debug!("State transition {:?} -> {:?}", app_state.current, next);
+
+    app_state.transition = Some(Transition {
+        from: Some(app_state.current.clone()),
+        to: next,
+    });
+}
+
+fn sys_apply_transition<S: State>(mut app_state: ResMut<AppState<S>>) {
+    if let Some(transition) = &app_state.transition {
+        app_state.current = transition.to.clone();
+    }
+}
+
+fn sys_end_transition<S: State>(mut app_state: ResMut<AppState<S>>) {
+    if app_state.transition.is_some() {
+        app_state.transition = None;
+    }
+}
+
+//--------------------------------------------------
+
+// Stage run condition that only runs the stage while in the given state
+pub fn in_state<S: State>(state: S) -> impl Fn(&World) -> bool {
+    move |world: &World| match world.borrow::<Res<AppState<S>>>() {
+        Ok(app_state) => app_state.is(&state),
+        Err(_) => false,
+    }
+}
+
+//====================================================================
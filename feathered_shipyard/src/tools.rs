@@ -32,7 +32,7 @@ pub trait UniqueTools {
 impl GetWorld for shipyard::World {
     #[inline]
     fn get_world(&self) -> &shipyard::World {
-        &self
+        self
     }
 }
 
@@ -0,0 +1,731 @@
+//====================================================================
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use feathered_common::{CommonPlugin, Time};
+use feathered_shipyard::prelude::*;
+use shipyard::{Component, EntityId, Get, IntoIter, IntoWithId};
+
+use crate::{hierarchy::Children, Transform};
+
+//====================================================================
+
+pub struct AnimationPlugin;
+
+impl Plugin for AnimationPlugin {
+    fn build_plugin(self, builder: &mut WorkloadBuilder) {
+        builder.add_workload_post(Update, sys_update_animation_players);
+    }
+
+    fn dependencies(&self) -> Vec<PluginDependency> {
+        vec![PluginDependency::plugin::<CommonPlugin>()]
+    }
+}
+
+//====================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Interpolation {
+    // Hold each keyframe until the next one
+    Step,
+    #[default]
+    Linear,
+    // Catmull-Rom spline through the keyframes
+    Cubic,
+}
+
+pub trait Keyframe: Copy {
+    fn interpolate(a: Self, b: Self, t: f32) -> Self;
+    fn cubic(p0: Self, p1: Self, p2: Self, p3: Self, t: f32) -> Self;
+}
+
+impl Keyframe for glam::Vec3 {
+    #[inline]
+    fn interpolate(a: Self, b: Self, t: f32) -> Self {
+        a.lerp(b, t)
+    }
+
+    #[inline]
+    fn cubic(p0: Self, p1: Self, p2: Self, p3: Self, t: f32) -> Self {
+        catmull_rom(
+            p0.extend(0.),
+            p1.extend(0.),
+            p2.extend(0.),
+            p3.extend(0.),
+            t,
+        )
+        .truncate()
+    }
+}
+
+impl Keyframe for glam::Quat {
+    #[inline]
+    fn interpolate(a: Self, b: Self, t: f32) -> Self {
+        a.slerp(b, t)
+    }
+
+    fn cubic(p0: Self, p1: Self, p2: Self, p3: Self, t: f32) -> Self {
+        // Keep all rotations in the same hemisphere as p1 so the spline takes the short path
+        let align = |q: glam::Quat| match q.dot(p1) < 0. {
+            true => -glam::Vec4::from(q),
+            false => glam::Vec4::from(q),
+        };
+
+        let p2 = align(p2);
+        let p3 = match glam::Vec4::from(p3).dot(p2) < 0. {
+            true => -glam::Vec4::from(p3),
+            false => glam::Vec4::from(p3),
+        };
+
+        glam::Quat::from_vec4(catmull_rom(align(p0), glam::Vec4::from(p1), p2, p3, t)).normalize()
+    }
+}
+
+#[inline]
+fn catmull_rom(
+    p0: glam::Vec4,
+    p1: glam::Vec4,
+    p2: glam::Vec4,
+    p3: glam::Vec4,
+    t: f32,
+) -> glam::Vec4 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+
+    0.5 * (2. * p1
+        + (p2 - p0) * t
+        + (2. * p0 - 5. * p1 + 4. * p2 - p3) * t2
+        + (3. * p1 - p0 - 3. * p2 + p3) * t3)
+}
+
+//--------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keyframes<T: Keyframe> {
+    // Seconds, in ascending order
+    times: Vec<f32>,
+    values: Vec<T>,
+    interpolation: Interpolation,
+}
+
+impl<T: Keyframe> Keyframes<T> {
+    // None if the number of times and values differ or the times aren't in ascending order
+    pub fn new(times: Vec<f32>, values: Vec<T>, interpolation: Interpolation) -> Option<Self> {
+        if times.len() != values.len() || !times.windows(2).all(|pair| pair[0] <= pair[1]) {
+            return None;
+        }
+
+        Some(Self {
+            times,
+            values,
+            interpolation,
+        })
+    }
+
+    // Keyframes are sorted by time. Keyframes with the same time keep their order.
+    pub fn linear(keyframes: impl IntoIterator<Item = (f32, T)>) -> Self {
+        let mut keyframes = keyframes.into_iter().collect::<Vec<_>>();
+        keyframes.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let (times, values) = keyframes.into_iter().unzip();
+
+        Self {
+            times,
+            values,
+            interpolation: Interpolation::Linear,
+        }
+    }
+
+    #[inline]
+    pub fn with_interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    #[inline]
+    pub fn interpolation(&self) -> Interpolation {
+        self.interpolation
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.times.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.times.is_empty()
+    }
+
+    #[inline]
+    pub fn duration(&self) -> f32 {
+        self.times.last().copied().unwrap_or(0.)
+    }
+
+    // Times before the first or after the last keyframe are clamped
+    pub fn sample(&self, time: f32) -> Option<T> {
+        let last = self.times.len().checked_sub(1)?;
+
+        if last == 0 || time <= self.times[0] {
+            return Some(self.values[0]);
+        }
+
+        if time >= self.times[last] {
+            return Some(self.values[last]);
+        }
+
+        let index = self.times.partition_point(|keyframe| *keyframe <= time) - 1;
+        let span = self.times[index + 1] - self.times[index];
+        let t = match span > 0. {
+            true => (time - self.times[index]) / span,
+            false => 1.,
+        };
+
+        Some(match self.interpolation {
+            Interpolation::Step => self.values[index],
+            Interpolation::Linear => T::interpolate(self.values[index], self.values[index + 1], t),
+            Interpolation::Cubic => T::cubic(
+                self.values[index.saturating_sub(1)],
+                self.values[index],
+                self.values[index + 1],
+                self.values[(index + 2).min(last)],
+                t,
+            ),
+        })
+    }
+}
+
+//====================================================================
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Track {
+    pub translation: Option<Keyframes<glam::Vec3>>,
+    pub rotation: Option<Keyframes<glam::Quat>>,
+    pub scale: Option<Keyframes<glam::Vec3>>,
+}
+
+impl Track {
+    #[inline]
+    pub fn with_translation(mut self, keyframes: Keyframes<glam::Vec3>) -> Self {
+        self.translation = Some(keyframes);
+        self
+    }
+
+    #[inline]
+    pub fn with_rotation(mut self, keyframes: Keyframes<glam::Quat>) -> Self {
+        self.rotation = Some(keyframes);
+        self
+    }
+
+    #[inline]
+    pub fn with_scale(mut self, keyframes: Keyframes<glam::Vec3>) -> Self {
+        self.scale = Some(keyframes);
+        self
+    }
+
+    pub fn duration(&self) -> f32 {
+        [
+            self.translation
+                .as_ref()
+                .map(|keyframes| keyframes.duration()),
+            self.rotation.as_ref().map(|keyframes| keyframes.duration()),
+            self.scale.as_ref().map(|keyframes| keyframes.duration()),
+        ]
+        .into_iter()
+        .flatten()
+        .fold(0., f32::max)
+    }
+
+    pub fn sample(&self, time: f32) -> TrackSample {
+        TrackSample {
+            translation: self
+                .translation
+                .as_ref()
+                .and_then(|keyframes| keyframes.sample(time)),
+            rotation: self
+                .rotation
+                .as_ref()
+                .and_then(|keyframes| keyframes.sample(time)),
+            scale: self
+                .scale
+                .as_ref()
+                .and_then(|keyframes| keyframes.sample(time)),
+        }
+    }
+}
+
+// A sampled track. Channels without keyframes are left as None and won't modify the transform.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TrackSample {
+    pub translation: Option<glam::Vec3>,
+    pub rotation: Option<glam::Quat>,
+    pub scale: Option<glam::Vec3>,
+}
+
+impl TrackSample {
+    // Weight of 0 returns this sample, 1 returns the other
+    pub fn blend(&self, other: &TrackSample, weight: f32) -> TrackSample {
+        fn blend_channel<T: Keyframe>(a: Option<T>, b: Option<T>, weight: f32) -> Option<T> {
+            match (a, b) {
+                (Some(a), Some(b)) => Some(T::interpolate(a, b, weight)),
+                (a, b) => a.or(b),
+            }
+        }
+
+        TrackSample {
+            translation: blend_channel(self.translation, other.translation, weight),
+            rotation: blend_channel(self.rotation, other.rotation, weight),
+            scale: blend_channel(self.scale, other.scale, weight),
+        }
+    }
+
+    pub fn apply(&self, transform: &mut Transform) {
+        if let Some(translation) = self.translation {
+            transform.translation = translation;
+        }
+        if let Some(rotation) = self.rotation {
+            transform.rotation = rotation;
+        }
+        if let Some(scale) = self.scale {
+            transform.scale = scale;
+        }
+    }
+}
+
+//--------------------------------------------------
+
+// Tracks are keyed by the name of the target they animate. See `AnimationTarget`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AnimationClip {
+    tracks: HashMap<String, Track>,
+    duration: f32,
+}
+
+impl AnimationClip {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn with_track(mut self, target: impl Into<String>, track: Track) -> Self {
+        self.add_track(target, track);
+        self
+    }
+
+    pub fn add_track(&mut self, target: impl Into<String>, track: Track) {
+        self.duration = self.duration.max(track.duration());
+        self.tracks.insert(target.into(), track);
+    }
+
+    #[inline]
+    pub fn track(&self, target: &str) -> Option<&Track> {
+        self.tracks.get(target)
+    }
+
+    #[inline]
+    pub fn tracks(&self) -> impl Iterator<Item = (&String, &Track)> {
+        self.tracks.iter()
+    }
+
+    // Seconds
+    #[inline]
+    pub fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    #[inline]
+    pub fn sample(&self, target: &str, time: f32) -> Option<TrackSample> {
+        self.tracks.get(target).map(|track| track.sample(time))
+    }
+}
+
+//====================================================================
+
+// Names an entity so it can be animated by an `AnimationPlayer` on itself or one of its ancestors
+#[derive(Component, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AnimationTarget(pub String);
+
+#[derive(Debug, Clone)]
+pub struct PlayingClip {
+    clip: Arc<AnimationClip>,
+    time: f32,
+    looping: bool,
+}
+
+impl PlayingClip {
+    #[inline]
+    pub fn clip(&self) -> &Arc<AnimationClip> {
+        &self.clip
+    }
+
+    #[inline]
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        !self.looping && self.time >= self.clip.duration
+    }
+
+    fn advance(&mut self, delta: f32) {
+        let duration = self.clip.duration;
+        self.time += delta;
+
+        if self.looping && duration > 0. {
+            self.time = self.time.rem_euclid(duration);
+        } else {
+            self.time = self.time.clamp(0., duration);
+        }
+    }
+
+    #[inline]
+    fn sample(&self, target: &str) -> Option<TrackSample> {
+        self.clip.sample(target, self.time)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Crossfade {
+    from: PlayingClip,
+    duration: f32,
+    elapsed: f32,
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct AnimationPlayer {
+    current: Option<PlayingClip>,
+    crossfade: Option<Crossfade>,
+    pub speed: f32,
+    pub paused: bool,
+}
+
+impl Default for AnimationPlayer {
+    fn default() -> Self {
+        Self {
+            current: None,
+            crossfade: None,
+            speed: 1.,
+            paused: false,
+        }
+    }
+}
+
+impl AnimationPlayer {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn with_clip(mut self, clip: Arc<AnimationClip>, looping: bool) -> Self {
+        self.play(clip, looping);
+        self
+    }
+
+    // Switch to the clip immediately
+    pub fn play(&mut self, clip: Arc<AnimationClip>, looping: bool) -> &mut Self {
+        self.crossfade = None;
+        self.current = Some(PlayingClip {
+            clip,
+            time: 0.,
+            looping,
+        });
+        self
+    }
+
+    // Blend from the current clip into the new one over the given duration
+    pub fn crossfade(
+        &mut self,
+        clip: Arc<AnimationClip>,
+        looping: bool,
+        duration: Duration,
+    ) -> &mut Self {
+        let from = self.current.take();
+
+        self.current = Some(PlayingClip {
+            clip,
+            time: 0.,
+            looping,
+        });
+
+        self.crossfade = from.map(|from| Crossfade {
+            from,
+            duration: duration.as_secs_f32(),
+            elapsed: 0.,
+        });
+
+        self
+    }
+
+    #[inline]
+    pub fn stop(&mut self) {
+        self.current = None;
+        self.crossfade = None;
+    }
+
+    #[inline]
+    pub fn seek(&mut self, time: f32) {
+        if let Some(current) = &mut self.current {
+            current.time = 0.;
+            current.advance(time);
+        }
+    }
+
+    #[inline]
+    pub fn current(&self) -> Option<&PlayingClip> {
+        self.current.as_ref()
+    }
+
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.current
+            .as_ref()
+            .map(|current| current.is_finished())
+            .unwrap_or(true)
+    }
+
+    #[inline]
+    pub fn is_crossfading(&self) -> bool {
+        self.crossfade.is_some()
+    }
+
+    pub fn tick(&mut self, delta: f32) {
+        if self.paused {
+            return;
+        }
+
+        let delta = delta * self.speed;
+
+        if let Some(current) = &mut self.current {
+            current.advance(delta);
+        }
+
+        if let Some(crossfade) = &mut self.crossfade {
+            crossfade.from.advance(delta);
+            crossfade.elapsed += delta.abs();
+
+            if crossfade.elapsed >= crossfade.duration {
+                self.crossfade = None;
+            }
+        }
+    }
+
+    // Sample the blended pose for a target
+    pub fn sample(&self, target: &str) -> Option<TrackSample> {
+        let current = self.current.as_ref()?.sample(target);
+
+        let Some(crossfade) = &self.crossfade else {
+            return current;
+        };
+
+        let weight = match crossfade.duration > 0. {
+            true => (crossfade.elapsed / crossfade.duration).clamp(0., 1.),
+            false => 1.,
+        };
+
+        match (crossfade.from.sample(target), current) {
+            (Some(from), Some(current)) => Some(from.blend(&current, weight)),
+            (from, current) => current.or(from),
+        }
+    }
+}
+
+//====================================================================
+
+fn sys_update_animation_players(
+    time: Res<Time>,
+    mut vm_player: ViewMut<AnimationPlayer>,
+    v_target: View<AnimationTarget>,
+    v_children: View<Children>,
+    mut vm_transform: ViewMut<Transform>,
+) {
+    let delta = time.delta_seconds();
+
+    (&mut vm_player)
+        .iter()
+        .with_id()
+        .for_each(|(entity, player)| {
+            // Finished clips have already been applied
+            if player.current.is_none() || (player.is_finished() && !player.is_crossfading()) {
+                return;
+            }
+
+            player.tick(delta);
+
+            let mut stack = vec![entity];
+
+            while let Some(entity) = stack.pop() {
+                apply_target(entity, player, &v_target, &mut vm_transform);
+
+                if let Ok(children) = v_children.get(entity) {
+                    stack.extend(children.iter());
+                }
+            }
+        });
+}
+
+#[inline]
+fn apply_target(
+    entity: EntityId,
+    player: &AnimationPlayer,
+    v_target: &View<AnimationTarget>,
+    vm_transform: &mut ViewMut<Transform>,
+) {
+    let Ok(target) = v_target.get(entity) else {
+        return;
+    };
+
+    let Some(sample) = player.sample(&target.0) else {
+        return;
+    };
+
+    if let Ok(mut transform) = (&mut *vm_transform).get(entity) {
+        sample.apply(&mut transform);
+    }
+}
+
+//====================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use super::{AnimationClip, AnimationPlayer, Interpolation, Keyframe, Keyframes, Track};
+
+    fn keyframes(values: &[f32], interpolation: Interpolation) -> Keyframes<glam::Vec3> {
+        Keyframes::new(
+            (0..values.len()).map(|time| time as f32).collect(),
+            values.iter().map(|x| glam::vec3(*x, 0., 0.)).collect(),
+            interpolation,
+        )
+        .unwrap()
+    }
+
+    fn sample_x(keyframes: &Keyframes<glam::Vec3>, time: f32) -> f32 {
+        keyframes.sample(time).unwrap().x
+    }
+
+    #[test]
+    fn new_rejects_invalid_keyframes() {
+        assert!(
+            Keyframes::new(vec![0., 1.], vec![glam::Vec3::ZERO], Interpolation::Linear).is_none()
+        );
+        assert!(Keyframes::new(
+            vec![1., 0.],
+            vec![glam::Vec3::ZERO; 2],
+            Interpolation::Linear
+        )
+        .is_none());
+        assert!(
+            Keyframes::<glam::Vec3>::new(Vec::new(), Vec::new(), Interpolation::Linear)
+                .unwrap()
+                .sample(0.)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn step_holds_each_keyframe() {
+        let keyframes = keyframes(&[0., 10., 20.], Interpolation::Step);
+
+        assert_eq!(sample_x(&keyframes, 0.5), 0.);
+        assert_eq!(sample_x(&keyframes, 1.), 10.);
+        assert_eq!(sample_x(&keyframes, 1.99), 10.);
+    }
+
+    #[test]
+    fn linear_interpolates_between_keyframes() {
+        let keyframes = keyframes(&[0., 10., 30.], Interpolation::Linear);
+
+        assert!((sample_x(&keyframes, 0.25) - 2.5).abs() < 1e-5);
+        assert!((sample_x(&keyframes, 1.5) - 20.).abs() < 1e-5);
+    }
+
+    #[test]
+    fn cubic_passes_through_keyframes() {
+        let keyframes = keyframes(&[0., 0., 1., 1.], Interpolation::Cubic);
+
+        assert!((sample_x(&keyframes, 1.) - 0.).abs() < 1e-5);
+        assert!((sample_x(&keyframes, 2.) - 1.).abs() < 1e-5);
+        // Eases in rather than following the straight line
+        assert!((sample_x(&keyframes, 1.25) - 0.203125).abs() < 1e-5);
+        assert!((sample_x(&keyframes, 1.5) - 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn times_outside_keyframes_are_clamped() {
+        let keyframes = keyframes(&[5., 10.], Interpolation::Linear);
+
+        assert_eq!(sample_x(&keyframes, -1.), 5.);
+        assert_eq!(sample_x(&keyframes, 100.), 10.);
+        assert_eq!(keyframes.duration(), 1.);
+    }
+
+    #[test]
+    fn duplicate_times_jump_to_the_later_keyframe() {
+        let keyframes = Keyframes::linear([
+            (0., glam::Vec3::ZERO),
+            (1., glam::Vec3::X),
+            (1., glam::Vec3::X * 5.),
+            (2., glam::Vec3::X * 6.),
+        ]);
+
+        assert!((sample_x(&keyframes, 0.5) - 0.5).abs() < 1e-5);
+        assert_eq!(sample_x(&keyframes, 1.), 5.);
+        assert!((sample_x(&keyframes, 1.5) - 5.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn linear_sorts_keyframes() {
+        let keyframes = Keyframes::linear([(1., glam::Vec3::X), (0., glam::Vec3::ZERO)]);
+
+        assert!((sample_x(&keyframes, 0.5) - 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn cubic_quaternions_take_the_short_path() {
+        let to = glam::Quat::from_rotation_y(std::f32::consts::FRAC_PI_2);
+
+        // The same rotation with the opposite sign
+        let result = glam::Quat::cubic(glam::Quat::IDENTITY, glam::Quat::IDENTITY, -to, -to, 0.5);
+        let expected = glam::Quat::IDENTITY.slerp(to, 0.5);
+
+        assert!(result.angle_between(expected) < 1e-3);
+    }
+
+    #[test]
+    fn crossfade_blends_by_elapsed_time() {
+        let clip = |x: f32| {
+            Arc::new(AnimationClip::new().with_track(
+                "target",
+                Track::default().with_translation(Keyframes::linear([
+                    (0., glam::vec3(x, 0., 0.)),
+                    (2., glam::vec3(x, 0., 0.)),
+                ])),
+            ))
+        };
+
+        let mut player = AnimationPlayer::new().with_clip(clip(0.), true);
+        player.crossfade(clip(10.), true, Duration::from_secs(1));
+
+        let sample_x =
+            |player: &AnimationPlayer| player.sample("target").unwrap().translation.unwrap().x;
+
+        assert_eq!(sample_x(&player), 0.);
+
+        player.tick(0.25);
+        assert!((sample_x(&player) - 2.5).abs() < 1e-5);
+
+        // Crossfades advance at the player's speed
+        player.speed = 2.;
+        player.tick(0.25);
+        assert!((sample_x(&player) - 7.5).abs() < 1e-5);
+
+        player.tick(0.25);
+        assert!(!player.is_crossfading());
+        assert_eq!(sample_x(&player), 10.);
+        assert!(player.sample("missing").is_none());
+    }
+}
@@ -0,0 +1,645 @@
+//====================================================================
+
+use std::collections::HashSet;
+
+use feathered_shipyard::{
+    prelude::*,
+    scene::{EntityMap, SceneComponent},
+};
+use serde::{Deserialize, Serialize};
+use shipyard::{
+    AddComponent, AllStorages, Component, Get, InsertionTracking, IntoIter, IntoWithId,
+    ModificationTracking, Remove,
+};
+
+use crate::GlobalTransform;
+
+//====================================================================
+
+// Axis aligned bounding box. As a component it holds the entity's bounds in local space.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[track(All)]
+pub struct Aabb {
+    pub min: glam::Vec3,
+    pub max: glam::Vec3,
+}
+
+impl SceneComponent for Aabb {
+    const NAME: &'static str = "Aabb";
+    type Data = Self;
+
+    #[inline]
+    fn save(&self, _: &AllStorages) -> Option<Self::Data> {
+        Some(*self)
+    }
+
+    #[inline]
+    fn load(data: Self::Data, _: &EntityMap, _: &AllStorages) -> Option<Self> {
+        Some(data)
+    }
+}
+
+impl Aabb {
+    #[inline]
+    pub fn new(min: glam::Vec3, max: glam::Vec3) -> Self {
+        Self {
+            min: min.min(max),
+            max: min.max(max),
+        }
+    }
+
+    #[inline]
+    pub fn from_center_half_extents(center: glam::Vec3, half_extents: glam::Vec3) -> Self {
+        Self::new(center - half_extents, center + half_extents)
+    }
+
+    #[inline]
+    pub fn from_point(point: glam::Vec3) -> Self {
+        Self {
+            min: point,
+            max: point,
+        }
+    }
+
+    pub fn from_points(points: impl IntoIterator<Item = glam::Vec3>) -> Option<Self> {
+        points.into_iter().fold(None, |acc, point| match acc {
+            Some(aabb) => Some(Aabb::merge(&aabb, &Aabb::from_point(point))),
+            None => Some(Aabb::from_point(point)),
+        })
+    }
+
+    #[inline]
+    pub fn center(&self) -> glam::Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    #[inline]
+    pub fn half_extents(&self) -> glam::Vec3 {
+        (self.max - self.min) * 0.5
+    }
+
+    #[inline]
+    pub fn size(&self) -> glam::Vec3 {
+        self.max - self.min
+    }
+
+    #[inline]
+    pub fn merge(&self, other: &Aabb) -> Aabb {
+        Aabb {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    #[inline]
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        self.min.cmple(other.max).all() && self.max.cmpge(other.min).all()
+    }
+
+    #[inline]
+    pub fn contains_point(&self, point: glam::Vec3) -> bool {
+        self.min.cmple(point).all() && self.max.cmpge(point).all()
+    }
+
+    #[inline]
+    pub fn contains(&self, other: &Aabb) -> bool {
+        self.contains_point(other.min) && self.contains_point(other.max)
+    }
+
+    #[inline]
+    pub fn closest_point(&self, point: glam::Vec3) -> glam::Vec3 {
+        point.clamp(self.min, self.max)
+    }
+
+    #[inline]
+    pub fn distance_squared(&self, point: glam::Vec3) -> f32 {
+        self.closest_point(point).distance_squared(point)
+    }
+
+    #[inline]
+    pub fn intersects_sphere(&self, center: glam::Vec3, radius: f32) -> bool {
+        self.distance_squared(center) <= radius * radius
+    }
+
+    // Bounds of this box after being transformed. Encloses the transformed box, so may be larger.
+    pub fn transformed(&self, transform: &glam::Affine3A) -> Aabb {
+        let center = transform.transform_point3(self.center());
+        let half_extents = self.half_extents();
+
+        let matrix = transform.matrix3;
+        let extents = glam::Vec3::new(
+            matrix.row(0).abs().dot(half_extents.into()),
+            matrix.row(1).abs().dot(half_extents.into()),
+            matrix.row(2).abs().dot(half_extents.into()),
+        );
+
+        Aabb::from_center_half_extents(center, extents)
+    }
+
+    // Distance along the ray to the first intersection, if any. Zero if the ray starts inside.
+    pub fn intersects_ray(&self, ray: &Ray) -> Option<f32> {
+        let inverse = ray.direction.recip();
+
+        let t1 = (self.min - ray.origin) * inverse;
+        let t2 = (self.max - ray.origin) * inverse;
+
+        // Axes the ray doesn't move along only need the origin within the box. Their slabs
+        // are NaN (0 * inf) when the origin lies on a face, so are left out.
+        let parallel = ray.direction.cmpeq(glam::Vec3::ZERO);
+        if (parallel & (ray.origin.cmplt(self.min) | ray.origin.cmpgt(self.max))).any() {
+            return None;
+        }
+
+        let near = glam::Vec3::select(parallel, glam::Vec3::NEG_INFINITY, t1.min(t2))
+            .max_element()
+            .max(0.);
+        let far = glam::Vec3::select(parallel, glam::Vec3::INFINITY, t1.max(t2)).min_element();
+
+        match near <= far {
+            true => Some(near),
+            false => None,
+        }
+    }
+}
+
+//====================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingSphere {
+    pub center: glam::Vec3,
+    pub radius: f32,
+}
+
+impl BoundingSphere {
+    #[inline]
+    pub fn new(center: glam::Vec3, radius: f32) -> Self {
+        Self {
+            center,
+            radius: radius.abs(),
+        }
+    }
+
+    #[inline]
+    pub fn from_aabb(aabb: &Aabb) -> Self {
+        Self {
+            center: aabb.center(),
+            radius: aabb.half_extents().length(),
+        }
+    }
+
+    // Centered on the bounds of the points, so not always the smallest possible sphere
+    pub fn from_points(points: impl IntoIterator<Item = glam::Vec3>) -> Option<Self> {
+        let points = points.into_iter().collect::<Vec<_>>();
+        let center = Aabb::from_points(points.iter().copied())?.center();
+
+        let radius = points
+            .iter()
+            .map(|point| point.distance_squared(center))
+            .fold(0., f32::max)
+            .sqrt();
+
+        Some(Self { center, radius })
+    }
+
+    #[inline]
+    pub fn to_aabb(&self) -> Aabb {
+        Aabb::from_center_half_extents(self.center, glam::Vec3::splat(self.radius))
+    }
+
+    pub fn merge(&self, other: &BoundingSphere) -> BoundingSphere {
+        let offset = other.center - self.center;
+        let distance = offset.length();
+
+        if distance + other.radius <= self.radius {
+            return *self;
+        }
+        if distance + self.radius <= other.radius {
+            return *other;
+        }
+
+        let radius = (distance + self.radius + other.radius) * 0.5;
+        let center = self.center + offset * ((radius - self.radius) / distance);
+
+        BoundingSphere { center, radius }
+    }
+
+    #[inline]
+    pub fn intersects(&self, other: &BoundingSphere) -> bool {
+        let radius = self.radius + other.radius;
+        self.center.distance_squared(other.center) <= radius * radius
+    }
+
+    #[inline]
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        aabb.intersects_sphere(self.center, self.radius)
+    }
+
+    #[inline]
+    pub fn contains_point(&self, point: glam::Vec3) -> bool {
+        self.center.distance_squared(point) <= self.radius * self.radius
+    }
+
+    #[inline]
+    pub fn contains(&self, other: &BoundingSphere) -> bool {
+        self.center.distance(other.center) + other.radius <= self.radius
+    }
+
+    // Radius is scaled by the largest axis scale, so non uniform scales give a larger sphere
+    pub fn transformed(&self, transform: &glam::Affine3A) -> BoundingSphere {
+        let matrix = transform.matrix3;
+        let scale = matrix
+            .x_axis
+            .length_squared()
+            .max(matrix.y_axis.length_squared())
+            .max(matrix.z_axis.length_squared())
+            .sqrt();
+
+        BoundingSphere {
+            center: transform.transform_point3(self.center),
+            radius: self.radius * scale,
+        }
+    }
+
+    // Distance along the ray to the first intersection, if any. Zero if the ray starts inside.
+    pub fn intersects_ray(&self, ray: &Ray) -> Option<f32> {
+        let offset = ray.origin - self.center;
+
+        let b = offset.dot(ray.direction);
+        let c = offset.length_squared() - self.radius * self.radius;
+
+        if c <= 0. {
+            return Some(0.);
+        }
+
+        let discriminant = b * b - c;
+        if b > 0. || discriminant < 0. {
+            return None;
+        }
+
+        Some(-b - discriminant.sqrt())
+    }
+}
+
+//====================================================================
+
+// World space bounds of an entity with both an `Aabb` and a `GlobalTransform`.
+// Kept up to date by the `SpatialPlugin`.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+#[track(All)]
+pub struct WorldBounds {
+    pub aabb: Aabb,
+    pub sphere: BoundingSphere,
+}
+
+impl WorldBounds {
+    pub fn new(aabb: &Aabb, global: &GlobalTransform) -> Self {
+        Self {
+            aabb: aabb.transformed(&global.0),
+            sphere: BoundingSphere::from_aabb(aabb).transformed(&global.0),
+        }
+    }
+}
+
+pub(crate) fn sys_update_world_bounds(
+    v_global: View<GlobalTransform>,
+    v_aabb: View<Aabb>,
+    mut vm_world: ViewMut<WorldBounds>,
+) {
+    v_aabb.removed_or_deleted().for_each(|entity| {
+        vm_world.remove(entity);
+    });
+
+    let changed = (v_global.inserted_or_modified(), &v_aabb)
+        .iter()
+        .with_id()
+        .map(|(id, _)| id)
+        .chain(
+            (&v_global, v_aabb.inserted_or_modified())
+                .iter()
+                .with_id()
+                .map(|(id, _)| id),
+        )
+        .collect::<HashSet<_>>();
+
+    changed.into_iter().for_each(|entity| {
+        let (global, aabb) = (&v_global, &v_aabb).get(entity).unwrap();
+        vm_world.add_component_unchecked(entity, WorldBounds::new(aabb, global));
+    });
+}
+
+//====================================================================
+
+// Components that local bounds can be computed from, such as meshes or sprites
+pub trait ComputeBounds: Component + Send + Sync {
+    fn compute_aabb(&self) -> Option<Aabb>;
+}
+
+pub trait BoundsBuilder {
+    // Keep the entity's `Aabb` in sync with the component whenever it is inserted or modified
+    fn compute_bounds<C>(&mut self) -> &mut Self
+    where
+        C: ComputeBounds,
+        C::Tracking: InsertionTracking + ModificationTracking;
+}
+
+impl BoundsBuilder for WorkloadBuilder<'_> {
+    fn compute_bounds<C>(&mut self) -> &mut Self
+    where
+        C: ComputeBounds,
+        C::Tracking: InsertionTracking + ModificationTracking,
+    {
+        self.get_inner().log(format!(
+            "Computing bounds for '{}'",
+            std::any::type_name::<C>()
+        ));
+
+        self.add_workload_post(Update, sys_compute_bounds::<C>)
+    }
+}
+
+fn sys_compute_bounds<C>(v_source: View<C>, mut vm_aabb: ViewMut<Aabb>)
+where
+    C: ComputeBounds,
+    C::Tracking: InsertionTracking + ModificationTracking,
+{
+    v_source
+        .inserted_or_modified()
+        .iter()
+        .with_id()
+        .for_each(|(entity, source)| match source.compute_aabb() {
+            Some(aabb) => {
+                // Avoid flagging the bounds as modified when nothing changed
+                if vm_aabb.get(entity).ok() != Some(&aabb) {
+                    vm_aabb.add_component_unchecked(entity, aabb);
+                }
+            }
+            None => {
+                vm_aabb.remove(entity);
+            }
+        });
+}
+
+//====================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ray {
+    pub origin: glam::Vec3,
+    // Normalized
+    pub direction: glam::Vec3,
+}
+
+impl Ray {
+    #[inline]
+    pub fn new(origin: glam::Vec3, direction: glam::Vec3) -> Self {
+        Self {
+            origin,
+            direction: direction.normalize_or_zero(),
+        }
+    }
+
+    #[inline]
+    pub fn at(&self, distance: f32) -> glam::Vec3 {
+        self.origin + self.direction * distance
+    }
+}
+
+//====================================================================
+
+#[cfg(test)]
+mod tests {
+    use feathered_runner::headless::HeadlessRunner;
+    use feathered_shipyard::prelude::*;
+    use shipyard::{AllStoragesViewMut, Component, EntityId, Get};
+
+    use super::{Aabb, BoundingSphere, BoundsBuilder, ComputeBounds, Ray, WorldBounds};
+    use crate::{SpatialPlugin, Transform};
+
+    const EPSILON: f32 = 1e-3;
+
+    struct Rng(u64);
+
+    impl Rng {
+        fn range(&mut self, min: f32, max: f32) -> f32 {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            min + (max - min) * ((self.0 >> 40) as f32 / (1u64 << 24) as f32)
+        }
+
+        fn vec3(&mut self, min: f32, max: f32) -> glam::Vec3 {
+            glam::vec3(
+                self.range(min, max),
+                self.range(min, max),
+                self.range(min, max),
+            )
+        }
+    }
+
+    fn assert_vec3_eq(a: glam::Vec3, b: glam::Vec3) {
+        assert!(a.abs_diff_eq(b, EPSILON), "{:?} != {:?}", a, b);
+    }
+
+    fn unit_box() -> Aabb {
+        Aabb::new(glam::Vec3::ZERO, glam::Vec3::ONE)
+    }
+
+    #[test]
+    fn transformed_aabb_fits_the_transformed_corners() {
+        let mut rng = Rng(1);
+
+        (0..200).for_each(|_| {
+            let aabb = Aabb::from_center_half_extents(rng.vec3(-5., 5.), rng.vec3(0., 3.));
+            let axis = rng.vec3(-1., 1.).try_normalize().unwrap_or(glam::Vec3::Y);
+            let transform = glam::Affine3A::from_scale_rotation_translation(
+                rng.vec3(0.5, 3.),
+                glam::Quat::from_axis_angle(axis, rng.range(-3., 3.)),
+                rng.vec3(-10., 10.),
+            );
+
+            let corners = (0..8).map(|i| {
+                let corner = glam::Vec3::select(
+                    glam::BVec3::new(i & 1 != 0, i & 2 != 0, i & 4 != 0),
+                    aabb.max,
+                    aabb.min,
+                );
+                transform.transform_point3(corner)
+            });
+            let expected = Aabb::from_points(corners).unwrap();
+
+            let transformed = aabb.transformed(&transform);
+            assert_vec3_eq(transformed.min, expected.min);
+            assert_vec3_eq(transformed.max, expected.max);
+        });
+    }
+
+    #[test]
+    fn aabb_ray_intersections() {
+        let aabb = unit_box();
+        let ray = |origin: glam::Vec3, direction: glam::Vec3| {
+            aabb.intersects_ray(&Ray::new(origin, direction))
+        };
+
+        assert_eq!(ray(glam::vec3(-2., 0.5, 0.5), glam::Vec3::X), Some(2.));
+        assert_eq!(ray(glam::vec3(3., 0.5, 0.5), glam::Vec3::NEG_X), Some(2.));
+        assert_eq!(ray(glam::vec3(0.5, 0.5, 0.5), glam::Vec3::Z), Some(0.));
+        assert_eq!(ray(glam::vec3(-2., 0.5, 0.5), glam::Vec3::NEG_X), None);
+        assert_eq!(ray(glam::vec3(-2., 2., 0.5), glam::Vec3::X), None);
+
+        let distance = ray(glam::Vec3::splat(-1.), glam::Vec3::ONE).unwrap();
+        assert!((distance - 3f32.sqrt()).abs() < EPSILON);
+    }
+
+    #[test]
+    fn axis_parallel_rays_on_faces_hit() {
+        let aabb = unit_box();
+        let ray = |origin: glam::Vec3, direction: glam::Vec3| {
+            aabb.intersects_ray(&Ray::new(origin, direction))
+        };
+
+        // Sliding along the lower and upper faces
+        assert_eq!(ray(glam::vec3(-1., 0., 0.5), glam::Vec3::X), Some(1.));
+        assert_eq!(ray(glam::vec3(-1., 1., 0.5), glam::Vec3::X), Some(1.));
+        assert_eq!(ray(glam::vec3(2., 0.5, 1.), glam::Vec3::NEG_X), Some(1.));
+        assert_eq!(ray(glam::vec3(0., -3., 0.), glam::Vec3::Y), Some(3.));
+
+        // Starting on a face
+        assert_eq!(ray(glam::vec3(0., 0.5, 0.5), glam::Vec3::X), Some(0.));
+        assert_eq!(ray(glam::vec3(0., 0.5, 0.5), glam::Vec3::NEG_X), Some(0.));
+        assert_eq!(ray(glam::vec3(0.5, 1., 0.5), glam::Vec3::Z), Some(0.));
+
+        // Parallel to a face but outside it
+        assert_eq!(ray(glam::vec3(-1., 1.5, 0.5), glam::Vec3::X), None);
+        assert_eq!(
+            ray(glam::vec3(0.5, -0.5, 1.5), glam::Vec3::Y.with_x(1.)),
+            None
+        );
+    }
+
+    #[test]
+    fn merged_spheres_contain_both() {
+        let a = BoundingSphere::new(glam::Vec3::ZERO, 2.);
+
+        // Either sphere inside the other
+        let inner = BoundingSphere::new(glam::vec3(0.5, 0., 0.), 1.);
+        assert_eq!(a.merge(&inner), a);
+        assert_eq!(inner.merge(&a), a);
+        assert_eq!(a.merge(&a), a);
+
+        let b = BoundingSphere::new(glam::vec3(6., 0., 0.), 1.);
+        let merged = a.merge(&b);
+        assert_vec3_eq(merged.center, glam::vec3(2.5, 0., 0.));
+        assert!((merged.radius - 4.5).abs() < EPSILON);
+
+        let mut rng = Rng(2);
+        (0..200).for_each(|_| {
+            let a = BoundingSphere::new(rng.vec3(-5., 5.), rng.range(0., 4.));
+            let b = BoundingSphere::new(rng.vec3(-5., 5.), rng.range(0., 4.));
+            let merged = a.merge(&b);
+
+            [a, b].iter().for_each(|sphere| {
+                let slack = BoundingSphere::new(merged.center, merged.radius + EPSILON);
+                assert!(slack.contains(sphere), "{:?} misses {:?}", merged, sphere);
+            });
+            assert!(merged.radius <= a.radius.max(b.radius) + a.center.distance(b.center));
+        });
+    }
+
+    #[test]
+    fn sphere_ray_intersections() {
+        let sphere = BoundingSphere::new(glam::vec3(5., 0., 0.), 1.);
+        let ray = |origin: glam::Vec3, direction: glam::Vec3| {
+            sphere.intersects_ray(&Ray::new(origin, direction))
+        };
+
+        assert_eq!(ray(glam::Vec3::ZERO, glam::Vec3::X), Some(4.));
+        assert_eq!(ray(glam::vec3(10., 0., 0.), glam::Vec3::NEG_X), Some(4.));
+        assert_eq!(ray(glam::vec3(5., 0.5, 0.), glam::Vec3::Y), Some(0.));
+        assert_eq!(ray(glam::Vec3::ZERO, glam::Vec3::NEG_X), None);
+        assert_eq!(ray(glam::Vec3::ZERO, glam::Vec3::Y), None);
+
+        // Grazing the top of the sphere
+        let distance = ray(glam::vec3(0., 1., 0.), glam::Vec3::X).unwrap();
+        assert!((distance - 5.).abs() < EPSILON);
+
+        let distance = ray(glam::vec3(5., -3., 0.5), glam::Vec3::Y).unwrap();
+        assert!((distance - (3. - 0.75f32.sqrt())).abs() < EPSILON);
+    }
+
+    //--------------------------------------------------
+
+    #[derive(Component)]
+    #[track(All)]
+    struct Shape(Option<Aabb>);
+
+    impl ComputeBounds for Shape {
+        fn compute_aabb(&self) -> Option<Aabb> {
+            self.0
+        }
+    }
+
+    fn world_bounds(runner: &HeadlessRunner, entity: EntityId) -> Option<WorldBounds> {
+        runner
+            .world()
+            .run(|v_world: View<WorldBounds>| v_world.get(entity).ok().copied())
+    }
+
+    fn local_bounds(runner: &HeadlessRunner, entity: EntityId) -> Option<Aabb> {
+        runner
+            .world()
+            .run(|v_aabb: View<Aabb>| v_aabb.get(entity).ok().copied())
+    }
+
+    #[test]
+    fn bounds_follow_sources_and_transforms() {
+        let mut runner = HeadlessRunner::new(|builder| {
+            builder.add_plugin(SpatialPlugin).compute_bounds::<Shape>();
+        });
+
+        let entity = runner.world().run(|mut all_storages: AllStoragesViewMut| {
+            all_storages.add_entity((
+                Transform::from_translation(glam::vec3(10., 0., 0.)),
+                Shape(Some(unit_box())),
+            ))
+        });
+        runner.step();
+
+        assert_eq!(local_bounds(&runner, entity), Some(unit_box()));
+        let bounds = world_bounds(&runner, entity).unwrap();
+        assert_eq!(
+            bounds.aabb,
+            Aabb::new(glam::vec3(10., 0., 0.), glam::vec3(11., 1., 1.))
+        );
+        assert_vec3_eq(bounds.sphere.center, glam::vec3(10.5, 0.5, 0.5));
+
+        runner.world().run(|mut vm_transform: ViewMut<Transform>| {
+            let mut transform = (&mut vm_transform).get(entity).unwrap();
+            transform.translation = glam::Vec3::ZERO;
+            transform.scale = glam::Vec3::splat(2.);
+        });
+        runner.step();
+        assert_eq!(
+            world_bounds(&runner, entity).unwrap().aabb,
+            Aabb::new(glam::Vec3::ZERO, glam::Vec3::splat(2.))
+        );
+
+        let resized = Aabb::new(glam::Vec3::NEG_ONE, glam::Vec3::ONE);
+        runner.world().run(|mut vm_shape: ViewMut<Shape>| {
+            (&mut vm_shape).get(entity).unwrap().0 = Some(resized);
+        });
+        runner.step();
+        assert_eq!(local_bounds(&runner, entity), Some(resized));
+        assert_eq!(
+            world_bounds(&runner, entity).unwrap().aabb,
+            Aabb::new(glam::Vec3::splat(-2.), glam::Vec3::splat(2.))
+        );
+
+        // Sources without bounds remove them
+        runner.world().run(|mut vm_shape: ViewMut<Shape>| {
+            (&mut vm_shape).get(entity).unwrap().0 = None;
+        });
+        runner.step();
+        assert_eq!(local_bounds(&runner, entity), None);
+        assert_eq!(world_bounds(&runner, entity), None);
+    }
+}
@@ -0,0 +1,361 @@
+//====================================================================
+
+use feathered_shipyard::{
+    commands::EntityCommands,
+    scene::{scene_id, EntityMap, SceneComponent},
+};
+use shipyard::{AddComponent, AllStorages, Component, EntityId, Get, Remove, ViewMut};
+
+//====================================================================
+
+// Parent and Children are kept in sync by the functions below (or the matching commands).
+// Inserting either component directly will leave the hierarchy in an inconsistent state.
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+#[track(All)]
+pub struct Parent(EntityId);
+
+impl Parent {
+    #[inline]
+    pub fn get(&self) -> EntityId {
+        self.0
+    }
+}
+
+#[derive(Component, Debug, Default, Clone, PartialEq, Eq)]
+#[track(All)]
+pub struct Children(Vec<EntityId>);
+
+impl SceneComponent for Parent {
+    const NAME: &'static str = "Parent";
+    type Data = u64;
+
+    #[inline]
+    fn save(&self, _: &AllStorages) -> Option<Self::Data> {
+        Some(scene_id(self.0))
+    }
+
+    // Skipped if the parent isn't part of the scene
+    #[inline]
+    fn load(data: Self::Data, entities: &EntityMap, _: &AllStorages) -> Option<Self> {
+        entities.get(data).map(Parent)
+    }
+}
+
+impl SceneComponent for Children {
+    const NAME: &'static str = "Children";
+    type Data = Vec<u64>;
+
+    #[inline]
+    fn save(&self, _: &AllStorages) -> Option<Self::Data> {
+        Some(self.0.iter().copied().map(scene_id).collect())
+    }
+
+    // Children that aren't part of the scene are dropped
+    fn load(data: Self::Data, entities: &EntityMap, _: &AllStorages) -> Option<Self> {
+        let children = data
+            .into_iter()
+            .filter_map(|id| entities.get(id))
+            .collect::<Vec<_>>();
+
+        match children.is_empty() {
+            true => None,
+            false => Some(Children(children)),
+        }
+    }
+}
+
+impl Children {
+    #[inline]
+    pub fn iter(&self) -> std::slice::Iter<'_, EntityId> {
+        self.0.iter()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+//====================================================================
+
+// Any previous parent is replaced. Fails if it would create a cycle.
+pub fn set_parent(all_storages: &mut AllStorages, child: EntityId, parent: EntityId) {
+    if child == parent || is_ancestor(all_storages, child, parent) {
+        log::warn!(
+            "Unable to set parent of {:?} to {:?} - would create a cycle",
+            child,
+            parent
+        );
+        return;
+    }
+
+    if !all_storages.is_entity_alive(parent) {
+        log::warn!(
+            "Unable to set parent of {:?} to {:?} - parent doesn't exist",
+            child,
+            parent
+        );
+        return;
+    }
+
+    remove_parent(all_storages, child);
+    all_storages.add_component(child, (Parent(parent),));
+
+    let mut vm_children = all_storages.borrow::<ViewMut<Children>>().unwrap();
+    match (&mut vm_children).get(parent) {
+        Ok(mut children) => children.0.push(child),
+        Err(_) => vm_children.add_component_unchecked(parent, Children(vec![child])),
+    }
+}
+
+pub fn remove_parent(all_storages: &mut AllStorages, child: EntityId) {
+    let (Some(Parent(parent)),) = all_storages.remove::<(Parent,)>(child) else {
+        return;
+    };
+
+    let mut vm_children = all_storages.borrow::<ViewMut<Children>>().unwrap();
+
+    let now_empty = match (&mut vm_children).get(parent) {
+        Ok(mut children) => {
+            children.0.retain(|entity| *entity != child);
+            children.0.is_empty()
+        }
+        Err(_) => false,
+    };
+
+    if now_empty {
+        vm_children.remove(parent);
+    }
+}
+
+// Despawns the entity and all of its descendants
+pub fn despawn_recursive(all_storages: &mut AllStorages, entity: EntityId) {
+    remove_parent(all_storages, entity);
+
+    let to_despawn = descendants(all_storages, entity);
+
+    all_storages.delete_entity(entity);
+    to_despawn.into_iter().for_each(|entity| {
+        all_storages.delete_entity(entity);
+    });
+}
+
+pub fn descendants(all_storages: &AllStorages, entity: EntityId) -> Vec<EntityId> {
+    let v_children = all_storages.borrow::<shipyard::View<Children>>().unwrap();
+
+    let mut descendants = Vec::new();
+    let mut stack = vec![entity];
+
+    while let Some(entity) = stack.pop() {
+        if let Ok(children) = v_children.get(entity) {
+            children.iter().for_each(|child| {
+                descendants.push(*child);
+                stack.push(*child);
+            });
+        }
+    }
+
+    descendants
+}
+
+// Is 'ancestor' somewhere above 'entity' in the hierarchy
+pub fn is_ancestor(all_storages: &AllStorages, ancestor: EntityId, entity: EntityId) -> bool {
+    let v_parent = all_storages.borrow::<shipyard::View<Parent>>().unwrap();
+
+    let mut current = entity;
+    while let Ok(parent) = v_parent.get(current) {
+        if parent.0 == ancestor {
+            return true;
+        }
+        current = parent.0;
+    }
+
+    false
+}
+
+//====================================================================
+
+pub trait HierarchyCommands {
+    fn set_parent(&mut self, parent: EntityId) -> &mut Self;
+    fn remove_parent(&mut self) -> &mut Self;
+    fn despawn_recursive(&mut self) -> &mut Self;
+}
+
+impl HierarchyCommands for EntityCommands<'_> {
+    #[inline]
+    fn set_parent(&mut self, parent: EntityId) -> &mut Self {
+        self.add(move |entity, all_storages| set_parent(all_storages, entity, parent))
+    }
+
+    #[inline]
+    fn remove_parent(&mut self) -> &mut Self {
+        self.add(|entity, all_storages| remove_parent(all_storages, entity))
+    }
+
+    #[inline]
+    fn despawn_recursive(&mut self) -> &mut Self {
+        self.add(|entity, all_storages| despawn_recursive(all_storages, entity))
+    }
+}
+
+//====================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use feathered_shipyard::scene::{scene_id, Scene, SceneRegistry};
+    use shipyard::{AllStoragesView, AllStoragesViewMut, EntityId, Get, View, World};
+
+    use super::{despawn_recursive, is_ancestor, set_parent, Children, Parent};
+
+    fn world() -> World {
+        let world = World::new();
+        let mut registry = SceneRegistry::default();
+        registry.register::<Parent>();
+        registry.register::<Children>();
+        world.add_unique(registry);
+        world
+    }
+
+    #[test]
+    fn scenes_remap_hierarchy_references() {
+        let mut saved = world();
+        let parent = saved.add_entity(());
+        let children = [saved.add_entity(()), saved.add_entity(())];
+
+        saved.run(|mut all_storages: AllStoragesViewMut| {
+            children
+                .iter()
+                .for_each(|child| set_parent(&mut all_storages, *child, parent))
+        });
+
+        let ron = Scene::from_world(&saved.borrow::<AllStoragesView>().unwrap())
+            .unwrap()
+            .to_ron()
+            .unwrap();
+
+        // Existing entities take the ids the scene was saved with
+        let mut loaded = world();
+        let existing = (0..5).map(|_| loaded.add_entity(())).collect::<Vec<_>>();
+        assert!(existing.contains(&parent));
+
+        let entities = {
+            let registry = loaded
+                .borrow::<feathered_shipyard::Res<SceneRegistry>>()
+                .unwrap();
+            let scene = Scene::from_ron(&registry, &ron).unwrap();
+            drop(registry);
+
+            scene
+                .spawn(&loaded.borrow::<AllStoragesView>().unwrap())
+                .unwrap()
+        };
+
+        let new_parent = entities.get(scene_id(parent)).unwrap();
+        let new_children = children
+            .iter()
+            .map(|child| entities.get(scene_id(*child)).unwrap())
+            .collect::<Vec<EntityId>>();
+
+        assert!(!existing.contains(&new_parent));
+        assert!(new_children.iter().all(|child| !existing.contains(child)));
+
+        loaded.run(|v_parent: View<Parent>, v_children: View<Children>| {
+            new_children.iter().for_each(|child| {
+                assert_eq!(v_parent.get(*child).unwrap().get(), new_parent);
+            });
+            assert_eq!(
+                v_children
+                    .get(new_parent)
+                    .unwrap()
+                    .iter()
+                    .copied()
+                    .collect::<Vec<_>>(),
+                new_children
+            );
+
+            existing.iter().for_each(|entity| {
+                assert!(v_parent.get(*entity).is_err());
+                assert!(v_children.get(*entity).is_err());
+            });
+        });
+    }
+
+    fn hierarchy(world: &mut World) -> [EntityId; 4] {
+        let [root, middle, leaf, sibling] = [(); 4].map(|_| world.add_entity(()));
+
+        world.run(|mut all_storages: AllStoragesViewMut| {
+            set_parent(&mut all_storages, middle, root);
+            set_parent(&mut all_storages, leaf, middle);
+            set_parent(&mut all_storages, sibling, root);
+        });
+
+        [root, middle, leaf, sibling]
+    }
+
+    #[test]
+    fn despawn_recursive_removes_the_subtree() {
+        let mut world = World::new();
+        let [root, middle, leaf, sibling] = hierarchy(&mut world);
+
+        world.run(|mut all_storages: AllStoragesViewMut| {
+            despawn_recursive(&mut all_storages, middle);
+
+            assert!(!all_storages.is_entity_alive(middle));
+            assert!(!all_storages.is_entity_alive(leaf));
+            assert!(all_storages.is_entity_alive(root));
+            assert!(all_storages.is_entity_alive(sibling));
+        });
+
+        world.run(|v_children: View<Children>| {
+            assert_eq!(
+                v_children.get(root).unwrap().iter().collect::<Vec<_>>(),
+                vec![&sibling]
+            );
+        });
+    }
+
+    #[test]
+    fn set_parent_refuses_cycles() {
+        let mut world = World::new();
+        let [root, middle, leaf, _] = hierarchy(&mut world);
+
+        world.run(|mut all_storages: AllStoragesViewMut| {
+            set_parent(&mut all_storages, root, leaf);
+            set_parent(&mut all_storages, middle, middle);
+
+            assert!(is_ancestor(&all_storages, root, leaf));
+            assert!(!is_ancestor(&all_storages, leaf, root));
+        });
+
+        world.run(|v_parent: View<Parent>| {
+            assert!(v_parent.get(root).is_err());
+            assert_eq!(v_parent.get(middle).unwrap().get(), root);
+        });
+    }
+
+    #[test]
+    fn dirty_ancestor_checks_stop_at_cycles() {
+        let mut world = World::new();
+        let [first, second, other] = [(); 3].map(|_| world.add_entity(()));
+
+        // Only possible by inserting 'Parent' directly
+        world.add_component(first, Parent(second));
+        world.add_component(second, Parent(first));
+
+        world.run(|v_parent: View<Parent>| {
+            let dirty = HashSet::from([other]);
+            assert!(!crate::has_dirty_ancestor(first, &v_parent, &dirty));
+
+            let dirty = HashSet::from([second]);
+            assert!(crate::has_dirty_ancestor(first, &v_parent, &dirty));
+        });
+    }
+}
@@ -1,7 +1,24 @@
 //====================================================================
 
-use feathered_shipyard::prelude::*;
-use shipyard::{Component, IntoIter};
+use std::collections::HashSet;
+
+use bounds::Aabb;
+use feathered_shipyard::{
+    prelude::*,
+    scene::{EntityMap, SceneBuilder, SceneComponent},
+};
+use hierarchy::{Children, Parent};
+use serde::{Deserialize, Serialize};
+use shipyard::{
+    AddComponent, AllStorages, Component, EntityId, Get, IntoIter, IntoWithId, IntoWorkload,
+    SystemModificator, Unique,
+};
+
+pub mod animation;
+pub mod bounds;
+pub mod hierarchy;
+pub mod spatial_index;
+pub mod tween;
 
 //====================================================================
 
@@ -9,14 +26,178 @@ pub struct SpatialPlugin;
 
 impl Plugin for SpatialPlugin {
     fn build_plugin(self, builder: &mut WorkloadBuilder) {
-        builder.add_workload_last(Update, sys_update_global);
+        builder
+            .register_scene_component::<Transform>()
+            .register_scene_component::<Parent>()
+            .register_scene_component::<Children>()
+            .register_scene_component::<Aabb>()
+            .add_workload_last(
+                Update,
+                (
+                    sys_insert_global,
+                    sys_update_global.after_all(sys_insert_global),
+                    bounds::sys_update_world_bounds.after_all(sys_update_global),
+                ),
+            );
     }
 }
 
-fn sys_update_global(v_transform: View<Transform>, mut vm_global: ViewMut<GlobalTransform>) {
-    (v_transform.inserted_or_modified(), &mut vm_global)
+pub trait TransformBuilder {
+    // Warn about entities with component 'C' that have no Transform, as they won't be rendered
+    fn require_transform<C: Component + Send + Sync>(&mut self) -> &mut Self;
+}
+
+impl TransformBuilder for WorkloadBuilder<'_> {
+    fn require_transform<C: Component + Send + Sync>(&mut self) -> &mut Self {
+        self.get_inner().log(format!(
+            "Requiring transform for '{}'",
+            std::any::type_name::<C>()
+        ));
+
+        if self
+            .get_world()
+            .borrow::<Res<MissingTransformWarnings>>()
+            .is_err()
+        {
+            self.insert(MissingTransformWarnings::default());
+        }
+
+        self.get_inner().add_workload_sub(
+            RenderPrep,
+            SubStages::First,
+            sys_check_missing_transform::<C>.into_workload(),
+            true,
+        );
+
+        self
+    }
+}
+
+//====================================================================
+
+fn sys_insert_global(v_transform: View<Transform>, mut vm_global: ViewMut<GlobalTransform>) {
+    let missing = v_transform
+        .inserted()
         .iter()
-        .for_each(|(transform, mut global)| global.0 = transform.to_affine());
+        .with_id()
+        .filter(|(id, _)| !vm_global.contains(*id))
+        .map(|(id, _)| id)
+        .collect::<Vec<_>>();
+
+    missing.into_iter().for_each(|id| {
+        vm_global.add_component_unchecked(id, GlobalTransform::default());
+    });
+}
+
+// Only recomputes the subtrees of entities whose transform or parent changed since the last run
+fn sys_update_global(
+    v_transform: View<Transform>,
+    v_parent: View<Parent>,
+    v_children: View<Children>,
+    mut vm_global: ViewMut<GlobalTransform>,
+) {
+    let dirty = v_transform
+        .inserted_or_modified()
+        .iter()
+        .with_id()
+        .map(|(id, _)| id)
+        .chain(
+            v_parent
+                .inserted_or_modified()
+                .iter()
+                .with_id()
+                .map(|(id, _)| id),
+        )
+        .chain(v_parent.removed_or_deleted())
+        .chain(vm_global.inserted().iter().with_id().map(|(id, _)| id))
+        .collect::<HashSet<_>>();
+
+    if dirty.is_empty() {
+        return;
+    }
+
+    let mut visited = HashSet::new();
+
+    dirty
+        .iter()
+        .filter(|entity| !has_dirty_ancestor(**entity, &v_parent, &dirty))
+        .for_each(|entity| {
+            let parent_global = v_parent
+                .get(*entity)
+                .ok()
+                .and_then(|parent| (&vm_global).get(parent.get()).ok())
+                .map(|global| global.0)
+                .unwrap_or(glam::Affine3A::IDENTITY);
+
+            let mut stack = vec![(*entity, parent_global)];
+
+            while let Some((entity, parent_global)) = stack.pop() {
+                if !visited.insert(entity) {
+                    continue;
+                }
+
+                let global = match v_transform.get(entity) {
+                    Ok(transform) => parent_global * transform.to_affine(),
+                    Err(_) => parent_global,
+                };
+
+                if let Ok(mut entity_global) = (&mut vm_global).get(entity) {
+                    entity_global.0 = global;
+                }
+
+                if let Ok(children) = v_children.get(entity) {
+                    children
+                        .iter()
+                        .for_each(|child| stack.push((*child, global)));
+                }
+            }
+        });
+}
+
+#[derive(Unique, Default)]
+struct MissingTransformWarnings(HashSet<EntityId>);
+
+fn sys_check_missing_transform<C: Component + Send + Sync>(
+    mut warnings: ResMut<MissingTransformWarnings>,
+    v_component: View<C>,
+    v_transform: View<Transform>,
+    v_global: View<GlobalTransform>,
+) {
+    (&v_component, !&v_transform, !&v_global)
+        .iter()
+        .with_id()
+        .for_each(|(id, _)| {
+            if warnings.0.insert(id) {
+                log::warn!(
+                    "Entity {:?} has a '{}' but no Transform and won't be rendered",
+                    id,
+                    std::any::type_name::<C>()
+                );
+            }
+        });
+}
+
+fn has_dirty_ancestor(
+    entity: EntityId,
+    v_parent: &View<Parent>,
+    dirty: &HashSet<EntityId>,
+) -> bool {
+    let mut current = entity;
+    let mut visited = HashSet::new();
+
+    while let Ok(parent) = v_parent.get(current) {
+        if dirty.contains(&parent.get()) {
+            return true;
+        }
+
+        // Guard against cycles created by inserting 'Parent' directly
+        if !visited.insert(parent.get()) {
+            return false;
+        }
+        current = parent.get();
+    }
+
+    false
 }
 
 //====================================================================
@@ -42,6 +223,72 @@ impl GlobalTransform {
     }
 }
 
+impl GlobalTransform {
+    #[inline]
+    pub fn mul_transform(&self, other: &GlobalTransform) -> GlobalTransform {
+        GlobalTransform(self.0 * other.0)
+    }
+
+    #[inline]
+    pub fn inverse(&self) -> GlobalTransform {
+        GlobalTransform(self.0.inverse())
+    }
+
+    #[inline]
+    pub fn transform_point(&self, point: glam::Vec3) -> glam::Vec3 {
+        self.0.transform_point3(point)
+    }
+
+    // Ignores translation
+    #[inline]
+    pub fn transform_vector(&self, vector: glam::Vec3) -> glam::Vec3 {
+        self.0.transform_vector3(vector)
+    }
+
+    // Local transform to use for a child of 'parent' so it ends up at this global transform
+    #[inline]
+    pub fn compute_relative(&self, parent: &GlobalTransform) -> Transform {
+        parent.inverse().mul_transform(self).compute_transform()
+    }
+
+    #[inline]
+    pub fn compute_transform(&self) -> Transform {
+        let (scale, rotation, translation) = self.0.to_scale_rotation_translation();
+        Transform {
+            translation,
+            rotation,
+            scale,
+        }
+    }
+}
+
+impl std::ops::Mul for GlobalTransform {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.mul_transform(&rhs)
+    }
+}
+
+impl std::ops::Mul<&Transform> for &GlobalTransform {
+    type Output = GlobalTransform;
+
+    #[inline]
+    fn mul(self, rhs: &Transform) -> Self::Output {
+        GlobalTransform(self.0 * rhs.to_affine())
+    }
+}
+
+impl std::ops::Mul<glam::Vec3> for &GlobalTransform {
+    type Output = glam::Vec3;
+
+    #[inline]
+    fn mul(self, rhs: glam::Vec3) -> Self::Output {
+        self.transform_point(rhs)
+    }
+}
+
 impl From<&GlobalTransform> for glam::Mat4 {
     #[inline]
     fn from(value: &GlobalTransform) -> Self {
@@ -58,7 +305,7 @@ impl From<&GlobalTransform> for glam::Affine3A {
 
 //====================================================================
 
-#[derive(Component, Clone, Debug, PartialEq)]
+#[derive(Component, Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[track(All)]
 pub struct Transform {
     pub translation: glam::Vec3,
@@ -66,6 +313,21 @@ pub struct Transform {
     pub scale: glam::Vec3,
 }
 
+impl SceneComponent for Transform {
+    const NAME: &'static str = "Transform";
+    type Data = Self;
+
+    #[inline]
+    fn save(&self, _: &AllStorages) -> Option<Self::Data> {
+        Some(self.clone())
+    }
+
+    #[inline]
+    fn load(data: Self::Data, _: &EntityMap, _: &AllStorages) -> Option<Self> {
+        Some(data)
+    }
+}
+
 impl Default for Transform {
     fn default() -> Self {
         Self {
@@ -194,11 +456,51 @@ impl Transform {
     #[inline]
     pub fn lerp(&mut self, target: &Transform, s: f32) {
         self.translation = self.translation.lerp(target.translation, s);
-        self.rotation = self.rotation.lerp(target.rotation, s);
+        self.rotation = self.rotation.slerp(target.rotation, s);
         self.scale = self.scale.lerp(target.scale, s);
     }
 }
 
+// Exact for uniform scale. Non-uniform scale combined with rotation can introduce shear,
+// which a Transform can't represent, so the result is an approximation.
+impl Transform {
+    pub fn mul_transform(&self, other: &Transform) -> Transform {
+        Transform {
+            translation: self.transform_point(other.translation),
+            rotation: self.rotation * other.rotation,
+            scale: self.scale * other.scale,
+        }
+    }
+
+    pub fn inverse(&self) -> Transform {
+        let rotation = self.rotation.inverse();
+        let scale = self.scale.recip();
+
+        Transform {
+            translation: rotation * -self.translation * scale,
+            rotation,
+            scale,
+        }
+    }
+
+    #[inline]
+    pub fn transform_point(&self, point: glam::Vec3) -> glam::Vec3 {
+        self.translation + self.rotation * (self.scale * point)
+    }
+
+    // Ignores translation
+    #[inline]
+    pub fn transform_vector(&self, vector: glam::Vec3) -> glam::Vec3 {
+        self.rotation * (self.scale * vector)
+    }
+
+    // This transform expressed relative to 'parent', such that 'parent * relative == self'
+    #[inline]
+    pub fn compute_relative(&self, parent: &Transform) -> Transform {
+        parent.inverse().mul_transform(self)
+    }
+}
+
 impl Transform {
     #[inline]
     pub fn to_matrix(&self) -> glam::Mat4 {
@@ -242,61 +544,378 @@ impl From<&Transform> for glam::Affine3A {
 
 //--------------------------------------------------
 
-// TODO - Review these operations
-impl std::ops::Add for Transform {
+// Composition. 'a * b' applies 'b' first, then 'a', the same as multiplying matrices.
+impl std::ops::Mul for Transform {
     type Output = Self;
 
     #[inline]
-    fn add(self, rhs: Transform) -> Self::Output {
-        self + &rhs
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.mul_transform(&rhs)
     }
 }
 
-impl std::ops::AddAssign for Transform {
+impl std::ops::Mul<&Self> for Transform {
+    type Output = Self;
+
     #[inline]
-    fn add_assign(&mut self, rhs: Self) {
-        self.add_assign(&rhs);
+    fn mul(self, rhs: &Self) -> Self::Output {
+        self.mul_transform(rhs)
     }
 }
 
-impl std::ops::Sub for Transform {
-    type Output = Self;
+impl std::ops::MulAssign<&Self> for Transform {
+    #[inline]
+    fn mul_assign(&mut self, rhs: &Self) {
+        *self = self.mul_transform(rhs);
+    }
+}
 
+impl std::ops::MulAssign for Transform {
     #[inline]
-    fn sub(self, rhs: Self) -> Self::Output {
-        self - &rhs
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = self.mul_transform(&rhs);
     }
 }
 
-impl std::ops::Add<&Self> for Transform {
-    type Output = Self;
+impl std::ops::Mul<glam::Vec3> for &Transform {
+    type Output = glam::Vec3;
 
-    fn add(mut self, rhs: &Self) -> Self::Output {
-        self.translation += rhs.translation;
-        self.rotation = self.rotation.mul_quat(rhs.rotation);
-        self.scale *= rhs.scale;
-        self
+    #[inline]
+    fn mul(self, rhs: glam::Vec3) -> Self::Output {
+        self.transform_point(rhs)
     }
 }
 
-impl std::ops::AddAssign<&Self> for Transform {
-    fn add_assign(&mut self, rhs: &Self) {
-        self.translation += rhs.translation;
-        self.rotation = self.rotation.mul_quat(rhs.rotation);
-        self.scale *= rhs.scale;
+//====================================================================
+
+#[cfg(test)]
+mod tests {
+    use feathered_runner::headless::HeadlessRunner;
+    use feathered_shipyard::prelude::*;
+    use shipyard::{
+        AllStoragesViewMut, Component, EntityId, Get, IntoIter, IntoWithId, Unique, View, ViewMut,
+    };
+
+    use super::{
+        hierarchy::{remove_parent, set_parent, Children},
+        GlobalTransform, MissingTransformWarnings, SpatialPlugin, Transform, TransformBuilder,
+    };
+
+    const SAMPLES: usize = 200;
+    const EPSILON: f32 = 1e-3;
+
+    // Small LCG so the tests are deterministic without pulling in a rand crate
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> f32 {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (self.0 >> 40) as f32 / (1u64 << 24) as f32
+        }
+
+        fn range(&mut self, min: f32, max: f32) -> f32 {
+            min + (max - min) * self.next()
+        }
+
+        fn vec3(&mut self, min: f32, max: f32) -> glam::Vec3 {
+            glam::vec3(
+                self.range(min, max),
+                self.range(min, max),
+                self.range(min, max),
+            )
+        }
+
+        fn rotation(&mut self) -> glam::Quat {
+            let axis = self.vec3(-1., 1.).try_normalize().unwrap_or(glam::Vec3::Y);
+            glam::Quat::from_axis_angle(
+                axis,
+                self.range(-std::f32::consts::PI, std::f32::consts::PI),
+            )
+        }
+
+        fn uniform_transform(&mut self) -> Transform {
+            Transform::from_scale_rotation_translation(
+                glam::Vec3::splat(self.range(0.5, 2.)),
+                self.rotation(),
+                self.vec3(-10., 10.),
+            )
+        }
     }
-}
 
-impl std::ops::Sub<&Self> for Transform {
-    type Output = Self;
+    fn assert_affine_eq(a: glam::Affine3A, b: glam::Affine3A) {
+        assert!(a.abs_diff_eq(b, EPSILON), "{:?} != {:?}", a, b);
+    }
 
-    fn sub(mut self, rhs: &Self) -> Self::Output {
-        self.translation -= rhs.translation;
-        self.rotation = self.rotation.mul_quat(rhs.rotation.inverse());
-        self.scale /= rhs.scale;
+    fn assert_vec3_eq(a: glam::Vec3, b: glam::Vec3) {
+        assert!(a.abs_diff_eq(b, EPSILON), "{:?} != {:?}", a, b);
+    }
 
-        self
+    #[test]
+    fn transform_matches_affine() {
+        let mut rng = Rng(1);
+
+        (0..SAMPLES).for_each(|_| {
+            let a = rng.uniform_transform();
+            let b = rng.uniform_transform();
+            let point = rng.vec3(-10., 10.);
+
+            assert_affine_eq(
+                a.mul_transform(&b).to_affine(),
+                a.to_affine() * b.to_affine(),
+            );
+            assert_affine_eq(a.inverse().to_affine(), a.to_affine().inverse());
+
+            assert_vec3_eq(
+                a.transform_point(point),
+                a.to_affine().transform_point3(point),
+            );
+            assert_vec3_eq(
+                a.transform_vector(point),
+                a.to_affine().transform_vector3(point),
+            );
+
+            assert_affine_eq(
+                (a.clone() * b.compute_relative(&a)).to_affine(),
+                b.to_affine(),
+            );
+        });
     }
-}
 
-//====================================================================
+    #[test]
+    fn global_transform_matches_affine() {
+        let mut rng = Rng(2);
+
+        (0..SAMPLES).for_each(|_| {
+            let a = GlobalTransform(rng.uniform_transform().to_affine());
+            let b = GlobalTransform(rng.uniform_transform().to_affine());
+            let local = rng.uniform_transform();
+            let point = rng.vec3(-10., 10.);
+
+            assert_affine_eq(a.mul_transform(&b).0, a.0 * b.0);
+            assert_affine_eq((&a * &local).0, a.0 * local.to_affine());
+            assert_affine_eq(a.inverse().0, a.0.inverse());
+
+            assert_vec3_eq(a.transform_point(point), a.0.transform_point3(point));
+            assert_vec3_eq(a.transform_vector(point), a.0.transform_vector3(point));
+
+            assert_affine_eq((&a * &b.compute_relative(&a)).0, b.0);
+            assert_affine_eq(a.compute_transform().to_affine(), a.0);
+        });
+    }
+
+    #[test]
+    fn non_uniform_scale_approximation() {
+        // Non-uniform scale without rotation in the child is still exact
+        let parent =
+            Transform::from_scale_translation(glam::vec3(2., 1., 1.), glam::vec3(1., 2., 3.));
+        let child = Transform::from_translation(glam::vec3(1., 1., 0.));
+        assert_affine_eq(
+            parent.mul_transform(&child).to_affine(),
+            parent.to_affine() * child.to_affine(),
+        );
+
+        // A rotated child would need shear. Scale is applied along the child's axes
+        // instead of the parent's, so a child 'X' point lands on the parent's Y axis with
+        // the parent's X scale applied.
+        let child =
+            Transform::from_rotation(glam::Quat::from_rotation_z(std::f32::consts::FRAC_PI_2));
+        let approximate = parent.mul_transform(&child).transform_point(glam::Vec3::X);
+        let exact = (parent.to_affine() * child.to_affine()).transform_point3(glam::Vec3::X);
+
+        assert_vec3_eq(exact, glam::vec3(1., 3., 3.));
+        assert_vec3_eq(approximate, glam::vec3(1., 4., 3.));
+        // Translation is always exact
+        assert_vec3_eq(
+            parent.mul_transform(&child).translation,
+            (parent.to_affine() * child.to_affine()).translation.into(),
+        );
+    }
+
+    //--------------------------------------------------
+
+    fn spatial_app() -> HeadlessRunner {
+        HeadlessRunner::new(|builder| {
+            builder.add_plugin(SpatialPlugin);
+        })
+    }
+
+    fn spawn(runner: &HeadlessRunner, x: f32, parent: Option<EntityId>) -> EntityId {
+        runner.world().run(|mut all_storages: AllStoragesViewMut| {
+            let entity =
+                all_storages.add_entity((Transform::from_translation(glam::vec3(x, 0., 0.)),));
+            if let Some(parent) = parent {
+                set_parent(&mut all_storages, entity, parent);
+            }
+            entity
+        })
+    }
+
+    fn set_x(runner: &HeadlessRunner, entity: EntityId, x: f32) {
+        runner.world().run(|mut vm_transform: ViewMut<Transform>| {
+            (&mut vm_transform).get(entity).unwrap().translation.x = x;
+        });
+    }
+
+    fn global_x(runner: &HeadlessRunner, entity: EntityId) -> f32 {
+        runner
+            .world()
+            .run(|v_global: View<GlobalTransform>| v_global.get(entity).unwrap().translation().x)
+    }
+
+    #[test]
+    fn global_transforms_follow_the_hierarchy() {
+        let mut runner = spatial_app();
+        let root = spawn(&runner, 1., None);
+        let child = spawn(&runner, 1., Some(root));
+        let grandchild = spawn(&runner, 1., Some(child));
+
+        runner.step();
+        assert_eq!(global_x(&runner, grandchild), 3.);
+
+        set_x(&runner, root, 5.);
+        runner.step();
+        assert_eq!(global_x(&runner, child), 6.);
+        assert_eq!(global_x(&runner, grandchild), 7.);
+
+        runner.world().run(|mut all_storages: AllStoragesViewMut| {
+            remove_parent(&mut all_storages, grandchild)
+        });
+        runner.step();
+        assert_eq!(global_x(&runner, grandchild), 1.);
+    }
+
+    #[test]
+    fn only_dirty_subtrees_are_recomputed() {
+        let mut runner = spatial_app();
+        let first = spawn(&runner, 1., None);
+        let first_child = spawn(&runner, 1., Some(first));
+        let second = spawn(&runner, 10., None);
+        let second_child = spawn(&runner, 1., Some(second));
+
+        runner.step();
+        assert_eq!(global_x(&runner, second_child), 11.);
+
+        // Left alone unless its subtree changes
+        runner
+            .world()
+            .run(|mut vm_global: ViewMut<GlobalTransform>| {
+                (&mut vm_global).get(second_child).unwrap().0 = glam::Affine3A::IDENTITY;
+            });
+
+        set_x(&runner, first, 2.);
+        runner.step();
+        assert_eq!(global_x(&runner, first_child), 3.);
+        assert_eq!(global_x(&runner, second_child), 0.);
+
+        set_x(&runner, second, 20.);
+        runner.step();
+        assert_eq!(global_x(&runner, second_child), 21.);
+    }
+
+    #[test]
+    fn reparenting_moves_the_subtree() {
+        let mut runner = spatial_app();
+        let first = spawn(&runner, 1., None);
+        let second = spawn(&runner, 10., None);
+        let child = spawn(&runner, 1., Some(first));
+        let grandchild = spawn(&runner, 1., Some(child));
+
+        runner.step();
+        assert_eq!(global_x(&runner, grandchild), 3.);
+
+        runner.world().run(|mut all_storages: AllStoragesViewMut| {
+            set_parent(&mut all_storages, child, second)
+        });
+        runner.step();
+
+        assert_eq!(global_x(&runner, child), 11.);
+        assert_eq!(global_x(&runner, grandchild), 12.);
+
+        runner.world().run(|v_children: View<Children>| {
+            assert!(v_children.get(first).is_err());
+            assert_eq!(
+                v_children.get(second).unwrap().iter().collect::<Vec<_>>(),
+                vec![&child]
+            );
+        });
+    }
+
+    //--------------------------------------------------
+
+    #[derive(Unique, Default)]
+    struct Spawned {
+        sent: bool,
+        with_global: Vec<bool>,
+    }
+
+    fn sys_spawn_once(mut spawned: ResMut<Spawned>, mut commands: Commands) {
+        if !std::mem::replace(&mut spawned.sent, true) {
+            commands.spawn((Transform::from_translation(glam::vec3(1., 2., 3.)),));
+        }
+    }
+
+    fn sys_check_global(
+        mut spawned: ResMut<Spawned>,
+        v_transform: View<Transform>,
+        v_global: View<GlobalTransform>,
+    ) {
+        spawned.with_global = v_transform
+            .iter()
+            .with_id()
+            .map(|(id, _)| v_global.contains(id))
+            .collect();
+    }
+
+    #[test]
+    fn spawned_transforms_have_a_global_by_render_prep() {
+        let mut runner = HeadlessRunner::new(|builder| {
+            builder
+                .add_plugin(SpatialPlugin)
+                .insert(Spawned::default())
+                .add_workload(Update, sys_spawn_once)
+                .add_workload(RenderPrep, sys_check_global);
+        });
+
+        runner.step();
+
+        runner.world().run(|spawned: Res<Spawned>| {
+            assert_eq!(spawned.with_global, vec![true]);
+        });
+        runner.world().run(|v_global: View<GlobalTransform>| {
+            let global = v_global.iter().next().unwrap();
+            assert_eq!(global.translation(), glam::vec3(1., 2., 3.));
+        });
+    }
+
+    #[derive(Component)]
+    struct Drawn;
+
+    #[test]
+    fn missing_transforms_are_warned_about_once() {
+        let mut runner = HeadlessRunner::new(|builder| {
+            builder
+                .add_plugin(SpatialPlugin)
+                .require_transform::<Drawn>();
+        });
+
+        let [missing, placed] = runner.world().run(|mut all_storages: AllStoragesViewMut| {
+            [
+                all_storages.add_entity((Drawn,)),
+                all_storages.add_entity((Drawn, Transform::default())),
+            ]
+        });
+
+        runner.step_frames(3);
+
+        runner
+            .world()
+            .run(|warnings: Res<MissingTransformWarnings>| {
+                assert_eq!(warnings.0.len(), 1);
+                assert!(warnings.0.contains(&missing));
+                assert!(!warnings.0.contains(&placed));
+            });
+    }
+}
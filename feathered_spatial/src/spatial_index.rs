@@ -0,0 +1,763 @@
+//====================================================================
+
+use std::collections::{HashMap, HashSet};
+
+use feathered_shipyard::prelude::*;
+use shipyard::{EntityId, Get, IntoIter, IntoWithId, SystemModificator, Unique};
+
+use crate::{
+    bounds::{Aabb, Ray},
+    GlobalTransform, SpatialPlugin,
+};
+
+//====================================================================
+
+pub const DEFAULT_CELL_SIZE: f32 = 10.;
+
+// Entities covering more cells than this are kept in a separate list that every query checks
+const MAX_ENTITY_CELLS: i64 = 64;
+
+//====================================================================
+
+// Indexes every entity with a GlobalTransform. Entities with an `Aabb` use its world space
+// bounds, others are indexed as a point. Updated at the end of `Update`.
+pub struct SpatialIndexPlugin {
+    pub cell_size: f32,
+}
+
+impl Default for SpatialIndexPlugin {
+    fn default() -> Self {
+        Self {
+            cell_size: DEFAULT_CELL_SIZE,
+        }
+    }
+}
+
+impl Plugin for SpatialIndexPlugin {
+    fn build_plugin(self, builder: &mut WorkloadBuilder) {
+        builder
+            .insert(SpatialIndex::new(self.cell_size))
+            .add_workload_last(
+                Update,
+                sys_update_spatial_index.after_all(crate::sys_update_global),
+            );
+    }
+
+    fn dependencies(&self) -> Vec<PluginDependency> {
+        vec![PluginDependency::plugin::<SpatialPlugin>()]
+    }
+}
+
+fn sys_update_spatial_index(
+    mut index: ResMut<SpatialIndex>,
+    v_global: View<GlobalTransform>,
+    v_aabb: View<Aabb>,
+) {
+    v_global.removed_or_deleted().for_each(|entity| {
+        index.remove(entity);
+    });
+
+    let changed = v_global
+        .inserted_or_modified()
+        .iter()
+        .with_id()
+        .map(|(id, _)| id)
+        .chain(
+            v_aabb
+                .inserted_or_modified()
+                .iter()
+                .with_id()
+                .map(|(id, _)| id),
+        )
+        .chain(v_aabb.removed_or_deleted())
+        .collect::<HashSet<_>>();
+
+    changed.into_iter().for_each(|entity| {
+        let Ok(global) = v_global.get(entity) else {
+            return;
+        };
+
+        let bounds = match v_aabb.get(entity) {
+            Ok(aabb) => aabb.transformed(&global.0),
+            Err(_) => Aabb::from_point(global.translation()),
+        };
+
+        index.insert(entity, bounds);
+    });
+}
+
+//====================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit {
+    pub entity: EntityId,
+    pub distance: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct IndexEntry {
+    bounds: Aabb,
+    // None if the entity is oversized
+    cells: Option<(glam::IVec3, glam::IVec3)>,
+}
+
+// Uniform grid of world space bounds
+#[derive(Unique, Debug)]
+pub struct SpatialIndex {
+    cell_size: f32,
+    cells: HashMap<glam::IVec3, Vec<EntityId>>,
+    entries: HashMap<EntityId, IndexEntry>,
+    oversized: HashSet<EntityId>,
+    // Range of cells that have held an entity. Only grows until cleared.
+    occupied: Option<(glam::IVec3, glam::IVec3)>,
+}
+
+impl Default for SpatialIndex {
+    #[inline]
+    fn default() -> Self {
+        Self::new(DEFAULT_CELL_SIZE)
+    }
+}
+
+impl SpatialIndex {
+    pub fn new(cell_size: f32) -> Self {
+        if cell_size <= 0. {
+            log::warn!(
+                "Spatial index cell size must be positive. Using {}",
+                DEFAULT_CELL_SIZE
+            );
+        }
+
+        Self {
+            cell_size: match cell_size > 0. {
+                true => cell_size,
+                false => DEFAULT_CELL_SIZE,
+            },
+            cells: HashMap::new(),
+            entries: HashMap::new(),
+            oversized: HashSet::new(),
+            occupied: None,
+        }
+    }
+
+    #[inline]
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    #[inline]
+    pub fn contains(&self, entity: EntityId) -> bool {
+        self.entries.contains_key(&entity)
+    }
+
+    // World space bounds the entity was indexed with
+    #[inline]
+    pub fn bounds(&self, entity: EntityId) -> Option<&Aabb> {
+        self.entries.get(&entity).map(|entry| &entry.bounds)
+    }
+
+    #[inline]
+    fn cell(&self, point: glam::Vec3) -> glam::IVec3 {
+        (point / self.cell_size).floor().as_ivec3()
+    }
+
+    #[inline]
+    fn cell_range(&self, bounds: &Aabb) -> (glam::IVec3, glam::IVec3) {
+        (self.cell(bounds.min), self.cell(bounds.max))
+    }
+
+    #[inline]
+    fn cell_count((min, max): (glam::IVec3, glam::IVec3)) -> i64 {
+        (max - min + glam::IVec3::ONE)
+            .as_i64vec3()
+            .max(glam::I64Vec3::ZERO)
+            .element_product()
+    }
+
+    fn cells_in((min, max): (glam::IVec3, glam::IVec3)) -> impl Iterator<Item = glam::IVec3> {
+        (min.x..=max.x).flat_map(move |x| {
+            (min.y..=max.y).flat_map(move |y| (min.z..=max.z).map(move |z| glam::ivec3(x, y, z)))
+        })
+    }
+
+    // Cells on the surface of the cube 'radius' cells out from the center, limited to 'bounds'
+    fn shell_cells(
+        center: glam::IVec3,
+        radius: i32,
+        (min, max): (glam::IVec3, glam::IVec3),
+    ) -> impl Iterator<Item = glam::IVec3> {
+        let min = center.saturating_sub(glam::IVec3::splat(radius)).max(min);
+        let max = center.saturating_add(glam::IVec3::splat(radius)).min(max);
+
+        (min.x..=max.x).flat_map(move |x| {
+            (min.y..=max.y).flat_map(move |y| {
+                let on_face = (x - center.x).abs() == radius || (y - center.y).abs() == radius;
+
+                // Away from the x and y faces only the two z faces are part of the shell
+                let face = on_face.then_some(min.z..=max.z).into_iter().flatten();
+                let caps = (!on_face)
+                    .then_some([center.z - radius, center.z + radius])
+                    .into_iter()
+                    .flatten()
+                    .filter(move |z| (min.z..=max.z).contains(z));
+
+                face.chain(caps).map(move |z| glam::ivec3(x, y, z))
+            })
+        })
+    }
+}
+
+impl SpatialIndex {
+    // Inserts or updates the entity
+    pub fn insert(&mut self, entity: EntityId, bounds: Aabb) {
+        let range = self.cell_range(&bounds);
+
+        if let Some(entry) = self.entries.get_mut(&entity) {
+            if entry.cells == Some(range) {
+                entry.bounds = bounds;
+                return;
+            }
+            self.remove(entity);
+        }
+
+        let cells = match Self::cell_count(range) > MAX_ENTITY_CELLS {
+            true => {
+                self.oversized.insert(entity);
+                None
+            }
+            false => {
+                Self::cells_in(range)
+                    .for_each(|cell| self.cells.entry(cell).or_default().push(entity));
+
+                self.occupied = Some(match self.occupied {
+                    Some((min, max)) => (min.min(range.0), max.max(range.1)),
+                    None => range,
+                });
+
+                Some(range)
+            }
+        };
+
+        self.entries.insert(entity, IndexEntry { bounds, cells });
+    }
+
+    pub fn remove(&mut self, entity: EntityId) -> bool {
+        let Some(entry) = self.entries.remove(&entity) else {
+            return false;
+        };
+
+        match entry.cells {
+            Some(range) => Self::cells_in(range).for_each(|cell| {
+                if let Some(entities) = self.cells.get_mut(&cell) {
+                    entities.retain(|other| *other != entity);
+                    if entities.is_empty() {
+                        self.cells.remove(&cell);
+                    }
+                }
+            }),
+            None => {
+                self.oversized.remove(&entity);
+            }
+        }
+
+        true
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.entries.clear();
+        self.oversized.clear();
+        self.occupied = None;
+    }
+
+    // Every entity in the cells, plus oversized entities. Falls back to every entity
+    // if checking the cells would be slower.
+    fn candidates(&self, range: (glam::IVec3, glam::IVec3)) -> HashSet<EntityId> {
+        if Self::cell_count(range) > self.entries.len() as i64 {
+            return self.entries.keys().copied().collect();
+        }
+
+        Self::cells_in(range)
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .chain(self.oversized.iter())
+            .copied()
+            .collect()
+    }
+}
+
+//--------------------------------------------------
+
+impl SpatialIndex {
+    pub fn query_aabb(&self, aabb: &Aabb) -> Vec<EntityId> {
+        self.candidates(self.cell_range(aabb))
+            .into_iter()
+            .filter(|entity| self.entries[entity].bounds.intersects(aabb))
+            .collect()
+    }
+
+    pub fn query_sphere(&self, center: glam::Vec3, radius: f32) -> Vec<EntityId> {
+        let range = self.cell_range(&Aabb::from_center_half_extents(
+            center,
+            glam::Vec3::splat(radius),
+        ));
+
+        self.candidates(range)
+            .into_iter()
+            .filter(|entity| {
+                self.entries[entity]
+                    .bounds
+                    .intersects_sphere(center, radius)
+            })
+            .collect()
+    }
+
+    // Hits sorted by distance. Unbounded rays check every entity.
+    pub fn raycast(&self, ray: &Ray, max_distance: f32) -> Vec<RayHit> {
+        let candidates = match max_distance.is_finite()
+            && (max_distance / self.cell_size) as usize * 3 < self.entries.len()
+        {
+            true => self.ray_candidates(ray, max_distance),
+            false => self.entries.keys().copied().collect(),
+        };
+
+        let mut hits = candidates
+            .into_iter()
+            .filter_map(|entity| {
+                let distance = self.entries[&entity].bounds.intersects_ray(ray)?;
+                (distance <= max_distance).then_some(RayHit { entity, distance })
+            })
+            .collect::<Vec<_>>();
+
+        hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        hits
+    }
+
+    // Walks the cells along the ray
+    fn ray_candidates(&self, ray: &Ray, max_distance: f32) -> HashSet<EntityId> {
+        let mut candidates = self.oversized.clone();
+
+        let mut cell = self.cell(ray.origin);
+        let step = ray.direction.signum().as_ivec3();
+
+        let next_boundary =
+            (cell.as_vec3() + ray.direction.signum().max(glam::Vec3::ZERO)) * self.cell_size;
+
+        let delta = (self.cell_size / ray.direction.abs()).to_array();
+        // Axes the ray doesn't move along are never stepped
+        let mut t_max = glam::Vec3::select(
+            ray.direction.cmpeq(glam::Vec3::ZERO),
+            glam::Vec3::INFINITY,
+            (next_boundary - ray.origin) / ray.direction,
+        )
+        .to_array();
+
+        let mut distance = 0.;
+
+        while distance <= max_distance {
+            if let Some(entities) = self.cells.get(&cell) {
+                candidates.extend(entities.iter());
+            }
+
+            let axis = match (
+                t_max[0] < t_max[1],
+                t_max[0] < t_max[2],
+                t_max[1] < t_max[2],
+            ) {
+                (true, true, _) => 0,
+                (false, _, true) => 1,
+                _ => 2,
+            };
+
+            distance = t_max[axis];
+            t_max[axis] += delta[axis];
+            cell[axis] += step[axis];
+        }
+
+        candidates
+    }
+
+    // The k entities with bounds closest to the point, along with their distance
+    pub fn nearest(&self, point: glam::Vec3, k: usize) -> Vec<(EntityId, f32)> {
+        if k == 0 || self.entries.is_empty() {
+            return Vec::new();
+        }
+
+        let distance =
+            |entity: &EntityId| self.entries[entity].bounds.distance_squared(point).sqrt();
+
+        let mut found = self
+            .oversized
+            .iter()
+            .map(|entity| (*entity, distance(entity)))
+            .collect::<Vec<_>>();
+        found.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+        let mut seen = HashSet::new();
+        let in_grid = self.entries.len() - self.oversized.len();
+
+        let center = self.cell(point);
+        let occupied = self.occupied.unwrap_or((center, center));
+
+        // Shells outside the occupied cells are empty, so skip straight to the first one
+        // that reaches them and stop once they've all been covered
+        let (min, max) = occupied;
+        let mut radius = min
+            .saturating_sub(center)
+            .max(center.saturating_sub(max))
+            .max(glam::IVec3::ZERO)
+            .max_element();
+        let max_radius = max
+            .saturating_sub(center)
+            .max(center.saturating_sub(min))
+            .max_element();
+
+        // Search outwards in shells of cells. Any entity not found after a shell
+        // is at least 'radius * cell_size' away from the point.
+        while seen.len() < in_grid && radius <= max_radius {
+            let found_before = found.len();
+
+            Self::shell_cells(center, radius, occupied)
+                .filter_map(|cell| self.cells.get(&cell))
+                .flatten()
+                .for_each(|entity| {
+                    if seen.insert(*entity) {
+                        found.push((*entity, distance(entity)));
+                    }
+                });
+
+            if found.len() != found_before {
+                found.sort_by(|a, b| a.1.total_cmp(&b.1));
+            }
+
+            if found.len() >= k && found[k - 1].1 <= radius as f32 * self.cell_size {
+                break;
+            }
+
+            radius += 1;
+        }
+
+        found.truncate(k);
+        found
+    }
+}
+
+//====================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use feathered_runner::headless::HeadlessRunner;
+    use feathered_shipyard::Res;
+    use shipyard::{AllStoragesViewMut, EntityId, Get, Remove, ViewMut, World};
+
+    use super::{RayHit, SpatialIndex, SpatialIndexPlugin};
+    use crate::{
+        bounds::{Aabb, Ray},
+        SpatialPlugin, Transform,
+    };
+
+    struct Rng(u64);
+
+    impl Rng {
+        fn range(&mut self, min: f32, max: f32) -> f32 {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            min + (max - min) * ((self.0 >> 40) as f32 / (1u64 << 24) as f32)
+        }
+
+        fn vec3(&mut self, min: f32, max: f32) -> glam::Vec3 {
+            glam::vec3(
+                self.range(min, max),
+                self.range(min, max),
+                self.range(min, max),
+            )
+        }
+    }
+
+    // Mix of points, small boxes spanning a few cells and oversized boxes
+    fn build_index(rng: &mut Rng, count: usize) -> (SpatialIndex, Vec<(EntityId, Aabb)>) {
+        let mut world = World::new();
+        let mut index = SpatialIndex::new(10.);
+
+        let entries = (0..count)
+            .map(|i| {
+                let center = rng.vec3(-200., 200.);
+                let half_extents = match i % 10 {
+                    0 => rng.vec3(20., 60.),
+                    1..=4 => rng.vec3(0., 15.),
+                    _ => glam::Vec3::ZERO,
+                };
+                let bounds = Aabb::from_center_half_extents(center, half_extents);
+                let entity = world.add_entity(());
+
+                index.insert(entity, bounds);
+                (entity, bounds)
+            })
+            .collect();
+
+        (index, entries)
+    }
+
+    fn brute_force(entries: &[(EntityId, Aabb)], point: glam::Vec3, k: usize) -> Vec<f32> {
+        let mut distances = entries
+            .iter()
+            .map(|(_, bounds)| bounds.distance_squared(point).sqrt())
+            .collect::<Vec<_>>();
+        distances.sort_by(f32::total_cmp);
+        distances.truncate(k);
+        distances
+    }
+
+    #[test]
+    fn nearest_matches_brute_force() {
+        let mut rng = Rng(3);
+        let (index, entries) = build_index(&mut rng, 300);
+
+        (0..100).for_each(|i| {
+            // Include points well outside the indexed area
+            let point = match i % 4 {
+                0 => rng.vec3(-2000., 2000.),
+                _ => rng.vec3(-250., 250.),
+            };
+            let k = 1 + i % 8;
+
+            let nearest = index.nearest(point, k);
+            let expected = brute_force(&entries, point, k);
+
+            assert_eq!(nearest.len(), expected.len());
+            nearest
+                .iter()
+                .zip(expected)
+                .for_each(|((_, distance), expected)| {
+                    assert!(
+                        (distance - expected).abs() < 1e-3,
+                        "{} != {}",
+                        distance,
+                        expected
+                    );
+                });
+        });
+    }
+
+    #[test]
+    fn nearest_handles_distant_points() {
+        let mut rng = Rng(4);
+        let (mut index, entries) = build_index(&mut rng, 50);
+
+        let point = glam::Vec3::splat(1e7);
+        assert_eq!(index.nearest(point, 3).len(), 3);
+        assert_eq!(index.nearest(point, 100).len(), 50);
+
+        entries.iter().for_each(|(entity, _)| {
+            index.remove(*entity);
+        });
+        assert!(index.nearest(point, 3).is_empty());
+    }
+
+    //--------------------------------------------------
+
+    fn brute_force_ray(entries: &[(EntityId, Aabb)], ray: &Ray, max_distance: f32) -> Vec<RayHit> {
+        let mut hits = entries
+            .iter()
+            .filter_map(|(entity, bounds)| {
+                let distance = bounds.intersects_ray(ray)?;
+                (distance <= max_distance).then_some(RayHit {
+                    entity: *entity,
+                    distance,
+                })
+            })
+            .collect::<Vec<_>>();
+        hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        hits
+    }
+
+    fn assert_hits_eq(hits: &[RayHit], expected: &[RayHit], ray: &Ray, max_distance: f32) {
+        assert_eq!(
+            hits.iter().map(|hit| hit.entity).collect::<HashSet<_>>(),
+            expected
+                .iter()
+                .map(|hit| hit.entity)
+                .collect::<HashSet<_>>(),
+            "{:?} up to {}",
+            ray,
+            max_distance
+        );
+        hits.iter().zip(expected).for_each(|(hit, expected)| {
+            assert!((hit.distance - expected.distance).abs() < 1e-3);
+        });
+    }
+
+    // Random directions, axis aligned directions and diagonals, half of them negative
+    fn ray_direction(rng: &mut Rng, i: usize) -> glam::Vec3 {
+        let sign = match i % 2 {
+            0 => 1.,
+            _ => -1.,
+        };
+        match i % 5 {
+            0 => glam::Vec3::X * sign,
+            1 => glam::Vec3::Y * sign,
+            2 => glam::Vec3::Z * sign,
+            3 => glam::vec3(1., 1., 0.) * sign,
+            _ => rng.vec3(-1., 1.),
+        }
+    }
+
+    // Origins anywhere, or exactly on cell boundaries
+    fn ray_origin(rng: &mut Rng, i: usize) -> glam::Vec3 {
+        match i % 3 {
+            0 => rng.vec3(-250., 250.),
+            _ => (rng.vec3(-25., 25.)).round() * 10.,
+        }
+    }
+
+    #[test]
+    fn raycast_matches_brute_force() {
+        let mut rng = Rng(5);
+        let (index, entries) = build_index(&mut rng, 300);
+
+        (0..200).for_each(|i| {
+            let ray = Ray::new(ray_origin(&mut rng, i), ray_direction(&mut rng, i));
+
+            // Shorter rays walk the cells, the rest fall back to checking every entity
+            [50., 300., 999., 1000., 5000., f32::INFINITY]
+                .into_iter()
+                .for_each(|max_distance| {
+                    assert_hits_eq(
+                        &index.raycast(&ray, max_distance),
+                        &brute_force_ray(&entries, &ray, max_distance),
+                        &ray,
+                        max_distance,
+                    );
+                });
+        });
+    }
+
+    #[test]
+    fn ray_candidates_include_every_hit() {
+        let mut rng = Rng(6);
+        let (index, entries) = build_index(&mut rng, 300);
+
+        (0..200).for_each(|i| {
+            let ray = Ray::new(ray_origin(&mut rng, i), ray_direction(&mut rng, i));
+
+            // Also walk distances that raycast would never walk
+            [50., 300., 1000.].into_iter().for_each(|max_distance| {
+                let candidates = index.ray_candidates(&ray, max_distance);
+                brute_force_ray(&entries, &ray, max_distance)
+                    .iter()
+                    .for_each(|hit| {
+                        assert!(
+                            candidates.contains(&hit.entity),
+                            "{:?} missed {:?} up to {}",
+                            ray,
+                            hit,
+                            max_distance
+                        );
+                    });
+            });
+        });
+    }
+
+    #[test]
+    fn queries_match_brute_force() {
+        let mut rng = Rng(7);
+        let (index, entries) = build_index(&mut rng, 300);
+
+        let collect = |entities: Vec<EntityId>| entities.into_iter().collect::<HashSet<_>>();
+
+        (0..100).for_each(|i| {
+            // Large queries fall back to checking every entity
+            let center = ray_origin(&mut rng, i);
+            let size = match i % 4 {
+                0 => rng.range(200., 500.),
+                _ => rng.range(0., 40.),
+            };
+
+            let aabb = Aabb::from_center_half_extents(center, rng.vec3(0., size));
+            let expected = entries
+                .iter()
+                .filter(|(_, bounds)| bounds.intersects(&aabb))
+                .map(|(entity, _)| *entity)
+                .collect::<HashSet<_>>();
+            assert_eq!(collect(index.query_aabb(&aabb)), expected);
+
+            let expected = entries
+                .iter()
+                .filter(|(_, bounds)| bounds.intersects_sphere(center, size))
+                .map(|(entity, _)| *entity)
+                .collect::<HashSet<_>>();
+            assert_eq!(collect(index.query_sphere(center, size)), expected);
+        });
+    }
+
+    //--------------------------------------------------
+
+    fn bounds(runner: &HeadlessRunner, entity: EntityId) -> Option<Aabb> {
+        runner
+            .world()
+            .run(|index: Res<SpatialIndex>| index.bounds(entity).copied())
+    }
+
+    #[test]
+    fn index_follows_transforms_and_bounds() {
+        let mut runner = HeadlessRunner::new(|builder| {
+            builder
+                .add_plugin(SpatialPlugin)
+                .add_plugin(SpatialIndexPlugin::default());
+        });
+
+        let entity = runner.world().run(|mut all_storages: AllStoragesViewMut| {
+            all_storages.add_entity((
+                Transform::default(),
+                Aabb::from_center_half_extents(glam::Vec3::ZERO, glam::Vec3::ONE),
+            ))
+        });
+        runner.step();
+        assert_eq!(
+            bounds(&runner, entity),
+            Some(Aabb::new(glam::Vec3::NEG_ONE, glam::Vec3::ONE))
+        );
+
+        runner.world().run(|mut vm_transform: ViewMut<Transform>| {
+            (&mut vm_transform).get(entity).unwrap().translation.x = 50.;
+        });
+        runner.step();
+        assert_eq!(
+            bounds(&runner, entity),
+            Some(Aabb::new(
+                glam::vec3(49., -1., -1.),
+                glam::vec3(51., 1., 1.)
+            ))
+        );
+
+        // Without bounds the entity is indexed as a point
+        runner.world().run(|mut vm_aabb: ViewMut<Aabb>| {
+            vm_aabb.remove(entity);
+        });
+        runner.step();
+        assert_eq!(
+            bounds(&runner, entity),
+            Some(Aabb::from_point(glam::vec3(50., 0., 0.)))
+        );
+
+        runner.world().run(|mut all_storages: AllStoragesViewMut| {
+            all_storages.delete_entity(entity);
+        });
+        runner.step();
+        assert_eq!(bounds(&runner, entity), None);
+    }
+}
@@ -0,0 +1,526 @@
+//====================================================================
+
+use std::time::Duration;
+
+use feathered_common::{CommonPlugin, Time};
+use feathered_shipyard::{
+    events::{Event, EventBuilder, EventSender, WriteEvents},
+    prelude::*,
+};
+use shipyard::{Component, EntityId, IntoIter, IntoWithId};
+
+use crate::Transform;
+
+//====================================================================
+
+pub struct TweenPlugin;
+
+impl Plugin for TweenPlugin {
+    fn build_plugin(self, builder: &mut WorkloadBuilder) {
+        builder
+            .register_event::<TweenCompleted>()
+            .add_workload_post(Update, sys_update_tweens);
+    }
+
+    fn dependencies(&self) -> Vec<PluginDependency> {
+        vec![PluginDependency::plugin::<CommonPlugin>()]
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TweenCompleted {
+    pub entity: EntityId,
+    pub id: u32,
+}
+
+//====================================================================
+
+#[derive(Debug, Clone, Copy)]
+pub enum Ease {
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    SineIn,
+    SineOut,
+    SineInOut,
+    ExpoIn,
+    ExpoOut,
+    ExpoInOut,
+    BackIn,
+    BackOut,
+    BackInOut,
+    ElasticOut,
+    BounceOut,
+    Custom(fn(f32) -> f32),
+}
+
+impl Ease {
+    pub fn apply(&self, t: f32) -> f32 {
+        use std::f32::consts::PI;
+
+        const BACK: f32 = 1.70158;
+        const BACK_IN_OUT: f32 = BACK * 1.525;
+
+        let t = t.clamp(0., 1.);
+
+        match self {
+            Ease::Linear => t,
+
+            Ease::QuadIn => t * t,
+            Ease::QuadOut => 1. - (1. - t) * (1. - t),
+            Ease::QuadInOut => match t < 0.5 {
+                true => 2. * t * t,
+                false => 1. - (-2. * t + 2.).powi(2) / 2.,
+            },
+
+            Ease::CubicIn => t * t * t,
+            Ease::CubicOut => 1. - (1. - t).powi(3),
+            Ease::CubicInOut => match t < 0.5 {
+                true => 4. * t * t * t,
+                false => 1. - (-2. * t + 2.).powi(3) / 2.,
+            },
+
+            Ease::SineIn => 1. - (t * PI / 2.).cos(),
+            Ease::SineOut => (t * PI / 2.).sin(),
+            Ease::SineInOut => -((PI * t).cos() - 1.) / 2.,
+
+            Ease::ExpoIn => match t == 0. {
+                true => 0.,
+                false => 2_f32.powf(10. * t - 10.),
+            },
+            Ease::ExpoOut => match t == 1. {
+                true => 1.,
+                false => 1. - 2_f32.powf(-10. * t),
+            },
+            Ease::ExpoInOut => match t {
+                0. => 0.,
+                1. => 1.,
+                t if t < 0.5 => 2_f32.powf(20. * t - 10.) / 2.,
+                t => (2. - 2_f32.powf(-20. * t + 10.)) / 2.,
+            },
+
+            Ease::BackIn => (BACK + 1.) * t * t * t - BACK * t * t,
+            Ease::BackOut => 1. + (BACK + 1.) * (t - 1.).powi(3) + BACK * (t - 1.).powi(2),
+            Ease::BackInOut => match t < 0.5 {
+                true => ((2. * t).powi(2) * ((BACK_IN_OUT + 1.) * 2. * t - BACK_IN_OUT)) / 2.,
+                false => {
+                    ((2. * t - 2.).powi(2) * ((BACK_IN_OUT + 1.) * (t * 2. - 2.) + BACK_IN_OUT)
+                        + 2.)
+                        / 2.
+                }
+            },
+
+            Ease::ElasticOut => match t {
+                0. => 0.,
+                1. => 1.,
+                t => 2_f32.powf(-10. * t) * ((t * 10. - 0.75) * (2. * PI / 3.)).sin() + 1.,
+            },
+
+            Ease::BounceOut => {
+                const N: f32 = 7.5625;
+                const D: f32 = 2.75;
+
+                if t < 1. / D {
+                    N * t * t
+                } else if t < 2. / D {
+                    let t = t - 1.5 / D;
+                    N * t * t + 0.75
+                } else if t < 2.5 / D {
+                    let t = t - 2.25 / D;
+                    N * t * t + 0.9375
+                } else {
+                    let t = t - 2.625 / D;
+                    N * t * t + 0.984375
+                }
+            }
+
+            Ease::Custom(ease) => ease(t),
+        }
+    }
+}
+
+//====================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TweenTarget {
+    Translation { from: glam::Vec3, to: glam::Vec3 },
+    Rotation { from: glam::Quat, to: glam::Quat },
+    Scale { from: glam::Vec3, to: glam::Vec3 },
+    Transform { from: Transform, to: Transform },
+}
+
+impl TweenTarget {
+    // Eased progress may overshoot 0..1 (back, elastic), so values are extrapolated where possible
+    pub fn apply(&self, transform: &mut Transform, t: f32) {
+        match self {
+            TweenTarget::Translation { from, to } => transform.translation = from.lerp(*to, t),
+            TweenTarget::Rotation { from, to } => transform.rotation = from.slerp(*to, t),
+            TweenTarget::Scale { from, to } => transform.scale = from.lerp(*to, t),
+            TweenTarget::Transform { from, to } => {
+                transform.translation = from.translation.lerp(to.translation, t);
+                transform.rotation = from.rotation.slerp(to.rotation, t);
+                transform.scale = from.scale.lerp(to.scale, t);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TweenStep {
+    pub target: TweenTarget,
+    pub duration: Duration,
+    pub ease: Ease,
+}
+
+impl TweenStep {
+    #[inline]
+    pub fn new(target: TweenTarget, duration: Duration) -> Self {
+        Self {
+            target,
+            duration,
+            ease: Ease::Linear,
+        }
+    }
+
+    #[inline]
+    pub fn with_ease(mut self, ease: Ease) -> Self {
+        self.ease = ease;
+        self
+    }
+
+    #[inline]
+    fn progress(&self, elapsed: Duration) -> f32 {
+        match self.duration.is_zero() {
+            true => 1.,
+            false => (elapsed.as_secs_f32() / self.duration.as_secs_f32()).min(1.),
+        }
+    }
+}
+
+//--------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Repeat {
+    // Number of times the tween is played. When ping-ponging, each direction counts as a play.
+    Times(u32),
+    Forever,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepeatMode {
+    // Jump back to the start of the sequence
+    Restart,
+    // Play the sequence backwards, then forwards again
+    PingPong,
+}
+
+//====================================================================
+
+#[derive(Component, Debug, Clone)]
+pub struct Tween {
+    steps: Vec<TweenStep>,
+    repeat: Repeat,
+    repeat_mode: RepeatMode,
+    id: u32,
+    pub paused: bool,
+
+    step: usize,
+    elapsed: Duration,
+    reversed: bool,
+    plays: u32,
+    finished: bool,
+}
+
+impl Tween {
+    pub fn new(target: TweenTarget, duration: Duration, ease: Ease) -> Self {
+        Self::from_step(TweenStep::new(target, duration).with_ease(ease))
+    }
+
+    pub fn from_step(step: TweenStep) -> Self {
+        Self {
+            steps: vec![step],
+            repeat: Repeat::Times(1),
+            repeat_mode: RepeatMode::Restart,
+            id: 0,
+            paused: false,
+
+            step: 0,
+            elapsed: Duration::ZERO,
+            reversed: false,
+            plays: 0,
+            finished: false,
+        }
+    }
+
+    // Append a step to run once the previous ones have finished
+    #[inline]
+    pub fn then(mut self, target: TweenTarget, duration: Duration, ease: Ease) -> Self {
+        self.steps
+            .push(TweenStep::new(target, duration).with_ease(ease));
+        self
+    }
+
+    #[inline]
+    pub fn then_step(mut self, step: TweenStep) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    #[inline]
+    pub fn with_repeat(mut self, repeat: Repeat, repeat_mode: RepeatMode) -> Self {
+        self.repeat = repeat;
+        self.repeat_mode = repeat_mode;
+        self
+    }
+
+    #[inline]
+    pub fn repeat_forever(self) -> Self {
+        self.with_repeat(Repeat::Forever, RepeatMode::Restart)
+    }
+
+    #[inline]
+    pub fn ping_pong(self) -> Self {
+        self.with_repeat(Repeat::Forever, RepeatMode::PingPong)
+    }
+
+    // Sent with the completion event to tell tweens apart
+    #[inline]
+    pub fn with_id(mut self, id: u32) -> Self {
+        self.id = id;
+        self
+    }
+}
+
+impl Tween {
+    #[inline]
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    #[inline]
+    pub fn steps(&self) -> &[TweenStep] {
+        &self.steps
+    }
+
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    // Number of times the whole sequence has been played
+    #[inline]
+    pub fn plays(&self) -> u32 {
+        self.plays
+    }
+
+    #[inline]
+    pub fn total_duration(&self) -> Duration {
+        self.steps.iter().map(|step| step.duration).sum()
+    }
+
+    pub fn reset(&mut self) {
+        self.step = 0;
+        self.elapsed = Duration::ZERO;
+        self.reversed = false;
+        self.plays = 0;
+        self.finished = false;
+    }
+
+    // Advance the tween and apply it to the transform. Returns true if it finished this tick.
+    pub fn tick(&mut self, delta: Duration, transform: &mut Transform) -> bool {
+        if self.finished || self.steps.is_empty() {
+            return false;
+        }
+
+        let mut remaining = delta;
+        let mut just_finished = false;
+
+        loop {
+            let left = self.steps[self.step].duration.saturating_sub(self.elapsed);
+
+            if remaining < left {
+                self.elapsed += remaining;
+                break;
+            }
+
+            remaining -= left;
+            self.elapsed = self.steps[self.step].duration;
+
+            let next = match self.reversed {
+                true => self.step.checked_sub(1),
+                false => Some(self.step + 1).filter(|next| *next < self.steps.len()),
+            };
+
+            if let Some(next) = next {
+                self.step = next;
+                self.elapsed = Duration::ZERO;
+                continue;
+            }
+
+            // Reached the end of the sequence
+            self.plays += 1;
+
+            if let Repeat::Times(times) = self.repeat {
+                if self.plays >= times {
+                    self.finished = true;
+                    just_finished = true;
+                    break;
+                }
+            }
+
+            match self.repeat_mode {
+                RepeatMode::Restart => self.step = 0,
+                RepeatMode::PingPong => self.reversed = !self.reversed,
+            }
+            self.elapsed = Duration::ZERO;
+
+            // Avoid looping forever on sequences with no duration
+            if self.total_duration().is_zero() {
+                break;
+            }
+        }
+
+        let step = &self.steps[self.step];
+        let progress = match self.reversed {
+            true => 1. - step.progress(self.elapsed),
+            false => step.progress(self.elapsed),
+        };
+
+        step.target.apply(transform, step.ease.apply(progress));
+
+        just_finished
+    }
+}
+
+//====================================================================
+
+fn sys_update_tweens(
+    time: Res<Time>,
+    mut vm_tween: ViewMut<Tween>,
+    mut vm_transform: ViewMut<Transform>,
+    mut completed: EventSender<TweenCompleted>,
+) {
+    let delta = *time.delta();
+
+    (&mut vm_tween, &mut vm_transform)
+        .iter()
+        .with_id()
+        .filter(|(_, (tween, _))| !tween.finished && !tween.paused)
+        .for_each(|(entity, (tween, mut transform))| {
+            if tween.tick(delta, &mut transform) {
+                completed.send_event(TweenCompleted {
+                    entity,
+                    id: tween.id,
+                });
+            }
+        });
+}
+
+//====================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Ease, Repeat, RepeatMode, Tween, TweenTarget};
+    use crate::Transform;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    fn move_x(from: f32, to: f32) -> TweenTarget {
+        TweenTarget::Translation {
+            from: glam::vec3(from, 0., 0.),
+            to: glam::vec3(to, 0., 0.),
+        }
+    }
+
+    fn tick_x(tween: &mut Tween, transform: &mut Transform, delta: Duration) -> (f32, bool) {
+        let finished = tween.tick(delta, transform);
+        (transform.translation.x, finished)
+    }
+
+    #[test]
+    fn leftover_delta_rolls_into_next_step() {
+        let mut tween = Tween::new(move_x(0., 10.), ms(100), Ease::Linear).then(
+            move_x(10., 20.),
+            ms(100),
+            Ease::Linear,
+        );
+        let mut transform = Transform::default();
+
+        let (x, _) = tick_x(&mut tween, &mut transform, ms(50));
+        assert!((x - 5.).abs() < 1e-4);
+
+        // 100ms finishes the first step with 50ms left for the second
+        let (x, _) = tick_x(&mut tween, &mut transform, ms(100));
+        assert!((x - 15.).abs() < 1e-4);
+    }
+
+    #[test]
+    fn ping_pong_reverses_at_each_end() {
+        let mut tween = Tween::new(move_x(0., 10.), ms(100), Ease::Linear)
+            .with_repeat(Repeat::Times(3), RepeatMode::PingPong);
+        let mut transform = Transform::default();
+
+        let (x, _) = tick_x(&mut tween, &mut transform, ms(125));
+        assert!((x - 7.5).abs() < 1e-4);
+
+        let (x, _) = tick_x(&mut tween, &mut transform, ms(50));
+        assert!((x - 2.5).abs() < 1e-4);
+
+        // Reaches the start and plays forwards again
+        let (x, _) = tick_x(&mut tween, &mut transform, ms(50));
+        assert!((x - 2.5).abs() < 1e-4);
+        assert_eq!(tween.plays(), 2);
+
+        let (x, finished) = tick_x(&mut tween, &mut transform, ms(100));
+        assert!((x - 10.).abs() < 1e-4);
+        assert!(finished);
+    }
+
+    #[test]
+    fn completion_fires_once() {
+        let mut tween = Tween::new(move_x(0., 10.), ms(100), Ease::Linear)
+            .with_repeat(Repeat::Times(2), RepeatMode::Restart);
+        let mut transform = Transform::default();
+
+        let (_, finished) = tick_x(&mut tween, &mut transform, ms(150));
+        assert!(!finished);
+
+        let (x, finished) = tick_x(&mut tween, &mut transform, ms(100));
+        assert!(finished);
+        assert!(tween.is_finished());
+        assert!((x - 10.).abs() < 1e-4);
+
+        let (x, finished) = tick_x(&mut tween, &mut transform, ms(100));
+        assert!(!finished);
+        assert!((x - 10.).abs() < 1e-4);
+        assert_eq!(tween.plays(), 2);
+    }
+
+    #[test]
+    fn zero_duration_tweens_do_not_hang() {
+        let mut transform = Transform::default();
+
+        let mut tween = Tween::new(move_x(0., 10.), Duration::ZERO, Ease::Linear).repeat_forever();
+        let (x, finished) = tick_x(&mut tween, &mut transform, ms(16));
+        assert!(!finished);
+        assert!((x - 10.).abs() < 1e-4);
+        assert_eq!(tween.plays(), 1);
+
+        // Sequences with no duration play at most once per tick
+        let mut tween = Tween::new(move_x(0., 10.), Duration::ZERO, Ease::Linear)
+            .with_repeat(Repeat::Times(2), RepeatMode::Restart);
+        let (_, finished) = tick_x(&mut tween, &mut transform, Duration::ZERO);
+        assert!(!finished);
+        let (_, finished) = tick_x(&mut tween, &mut transform, Duration::ZERO);
+        assert!(finished);
+        assert_eq!(tween.plays(), 2);
+    }
+}
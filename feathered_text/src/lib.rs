@@ -1,6 +1,6 @@
 //====================================================================
 
-use feathered_render_tools::{Device, SetupRendererComponents};
+use feathered_render_tools::{Device, RenderComponentsPlugin, SetupRendererComponents};
 use feathered_shipyard::prelude::*;
 use shipyard::{AllStoragesView, SystemModificator, Unique};
 use text_atlas::TextAtlas;
@@ -24,6 +24,10 @@ impl Plugin for CoreTextPlugin {
             )
             .add_workload(Last, sys_trim_atlas);
     }
+
+    fn dependencies(&self) -> Vec<PluginDependency> {
+        vec![PluginDependency::plugin::<RenderComponentsPlugin>()]
+    }
 }
 
 //====================================================================
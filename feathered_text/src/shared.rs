@@ -158,13 +158,10 @@ pub fn prep(
                     let physical = glyph.physical((0., 0.), 1.);
 
                     // Try to prep glyph in atlas
-                    if let Err(_) = text_atlas.use_glyph(
-                        device,
-                        queue,
-                        font_system,
-                        swash_cache,
-                        &physical.cache_key,
-                    ) {
+                    if text_atlas
+                        .use_glyph(device, queue, font_system, swash_cache, &physical.cache_key)
+                        .is_err()
+                    {
                         unimplemented!()
                     }
 
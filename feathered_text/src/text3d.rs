@@ -2,11 +2,11 @@
 
 use feathered_common::WasmWrapper;
 use feathered_render_tools::{
-    camera::Camera3d, shared::SharedRenderResources, Device, Queue, RenderPass, SurfaceConfig,
-    Vertex,
+    camera::Camera3d, shared::SharedRenderResources, Device, Queue, RenderPass, RenderUtilsPlugin,
+    SurfaceConfig, Vertex,
 };
 use feathered_shipyard::prelude::*;
-use feathered_spatial::Transform;
+use feathered_spatial::{Transform, TransformBuilder};
 use shipyard::{AllStoragesView, Component, IntoIter, SystemModificator, Unique};
 use wgpu::util::DeviceExt;
 
@@ -23,6 +23,7 @@ impl Plugin for Text3dPlugin {
     fn build_plugin(self, builder: &mut WorkloadBuilder) {
         builder
             .add_plugin(CoreTextPlugin)
+            .require_transform::<Text3dBuffer>()
             .add_workload_pre(Setup, sys_setup_text_renderer)
             .add_workload(RenderPrep, (sys_prep_text, sys_prep_text_transform))
             .add_workload(
@@ -30,6 +31,10 @@ impl Plugin for Text3dPlugin {
                 sys_render_text.skip_if_missing_unique::<RenderPass>(),
             );
     }
+
+    fn dependencies(&self) -> Vec<PluginDependency> {
+        vec![PluginDependency::plugin::<RenderUtilsPlugin>()]
+    }
 }
 
 fn sys_setup_text_renderer(
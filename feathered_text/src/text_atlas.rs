@@ -245,7 +245,7 @@ impl TextAtlas {
         self.packer.deallocate(val.alloc_id);
         self.cached_glyphs.pop(&key);
 
-        return Ok(());
+        Ok(())
     }
 
     #[inline]
@@ -2,14 +2,14 @@
 
 use std::{collections::HashSet, hash::Hash};
 
-use feathered_common::{WindowResizeEvent, WindowSize};
+use feathered_common::{CommonPlugin, WindowResizeEvent, WindowSize};
 use feathered_runner::events::{MouseButton, WindowInputEvent};
 use feathered_shipyard::{
-    builder::{First, Last, Plugin, WorkloadBuilder},
-    events::{EventBuilder, EventReader, ReadEvents},
+    builder::{First, Last, Plugin, PluginDependency, WorkloadBuilder},
+    events::{EventBuilder, EventHandle, EventReader, ReadEvents},
     Res, ResMut,
 };
-use shipyard::{IntoWorkload, Unique};
+use shipyard::{Get, IntoWorkload, Unique, View};
 
 pub use feathered_runner::events::KeyCode;
 
@@ -30,6 +30,14 @@ impl Plugin for KeyboardPlugin {
             .event_workload::<WindowResizeEvent>(First, sys_process_inputs.into_workload())
             .add_workload(Last, sys_reset_input::<KeyCode>);
     }
+
+    fn dependencies(&self) -> Vec<PluginDependency> {
+        vec![
+            PluginDependency::plugin::<CommonPlugin>(),
+            PluginDependency::unique::<WindowSize>(),
+            PluginDependency::unique::<EventHandle<WindowInputEvent>>(),
+        ]
+    }
 }
 
 pub struct MousePlugin;
@@ -44,6 +52,13 @@ impl Plugin for MousePlugin {
                 (sys_reset_input::<MouseButton>, sys_reset_mouse_input),
             );
     }
+
+    fn dependencies(&self) -> Vec<PluginDependency> {
+        vec![
+            PluginDependency::unique::<WindowSize>(),
+            PluginDependency::unique::<EventHandle<WindowInputEvent>>(),
+        ]
+    }
 }
 
 //====================================================================
@@ -51,42 +66,59 @@ impl Plugin for MousePlugin {
 fn sys_process_inputs(
     input_event: EventReader<WindowInputEvent>,
     size: Res<WindowSize>,
+    v_window_size: View<WindowSize>,
 
     mut keys: Option<ResMut<Input<KeyCode>>>,
     mut mouse_buttons: Option<ResMut<Input<MouseButton>>>,
     mut mouse_input: Option<ResMut<MouseInput>>,
 ) {
     input_event.iter().for_each(|event| match event {
-        WindowInputEvent::KeyInput { key, pressed } => match (&mut keys, pressed) {
+        WindowInputEvent::KeyInput { key, pressed, .. } => match (&mut keys, pressed) {
             (Some(keys), true) => keys.add_pressed(*key),
             (Some(keys), false) => keys.remove_pressed(*key),
             _ => {}
         },
 
-        WindowInputEvent::MouseInput { button, pressed } => match (&mut mouse_buttons, pressed) {
+        WindowInputEvent::MouseInput {
+            button, pressed, ..
+        } => match (&mut mouse_buttons, pressed) {
             (Some(buttons), true) => buttons.add_pressed(*button),
             (Some(buttons), false) => buttons.remove_pressed(*button),
             _ => {}
         },
 
-        WindowInputEvent::CursorMoved { position } => match &mut mouse_input {
-            Some(mouse) => {
+        WindowInputEvent::CursorMoved { window, position } => {
+            if let Some(mouse) = &mut mouse_input {
+                // Relative to whichever window the cursor is over
+                let height = v_window_size
+                    .get(*window)
+                    .map(|size| size.height_f32())
+                    .unwrap_or(size.height_f32());
+
                 mouse.position = glam::vec2(position.0 as f32, position.1 as f32);
-                mouse.screen_position =
-                    glam::vec2(mouse.position.x, size.height_f32() - mouse.position.y);
+                mouse.screen_position = glam::vec2(mouse.position.x, height - mouse.position.y);
             }
-            None => {}
-        },
+        }
 
-        WindowInputEvent::MouseWheel { delta } => match &mut mouse_input {
-            Some(mouse) => mouse.scroll = delta.clone().into(),
-            None => {}
-        },
+        WindowInputEvent::MouseWheel { delta, .. } => {
+            if let Some(mouse) = &mut mouse_input {
+                mouse.scroll += glam::Vec2::from(*delta)
+            }
+        }
 
-        WindowInputEvent::CursorMotion { delta } => match &mut mouse_input {
-            Some(mouse) => mouse.position_delta += glam::vec2(delta.0 as f32, delta.1 as f32),
-            None => {}
-        },
+        WindowInputEvent::MouseWheelPixels { delta, .. } => {
+            if let Some(mouse) = &mut mouse_input {
+                mouse.scroll_pixels += glam::vec2(delta.0 as f32, delta.1 as f32)
+            }
+        }
+
+        WindowInputEvent::CursorMotion { delta } => {
+            if let Some(mouse) = &mut mouse_input {
+                mouse.position_delta += glam::vec2(delta.0 as f32, delta.1 as f32)
+            }
+        }
+
+        _ => {}
     });
 }
 
@@ -166,6 +198,7 @@ pub struct MouseInput {
     screen_position: glam::Vec2,
     position_delta: glam::Vec2,
     scroll: glam::Vec2,
+    scroll_pixels: glam::Vec2,
 }
 
 impl MouseInput {
@@ -184,15 +217,23 @@ impl MouseInput {
         self.position_delta
     }
 
+    // Scroll in lines
     #[inline]
     pub fn scroll(&self) -> glam::Vec2 {
         self.scroll
     }
+
+    // Scroll in pixels, from trackpads and other precise devices
+    #[inline]
+    pub fn scroll_pixels(&self) -> glam::Vec2 {
+        self.scroll_pixels
+    }
 }
 
 fn sys_reset_mouse_input(mut mouse: ResMut<MouseInput>) {
     mouse.position_delta = glam::Vec2::ZERO;
     mouse.scroll = glam::Vec2::ZERO;
+    mouse.scroll_pixels = glam::Vec2::ZERO;
 }
 
 //====================================================================